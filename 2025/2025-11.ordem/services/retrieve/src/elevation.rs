@@ -0,0 +1,123 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// UAC Self-Elevation
+//
+// Querying services via WMI works as a normal user, but applying start-mode
+// changes requires administrator rights. Rather than fail with access-denied,
+// the backend detects its own integrity level and, if not elevated, re-launches
+// itself with the `runas` verb via `ShellExecuteW` so Windows shows the UAC
+// prompt -- the same bootstrap trick Windows `sudo` ports use to hand off to a
+// privileged child process.
+
+/// Returns whether the current process token is elevated (running as Administrator
+/// with UAC already satisfied).
+///
+/// Always returns `true` on non-Windows builds, since there is no equivalent
+/// elevation concept to check.
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token = Default::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut returned_len = 0u32;
+        let queried = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut _ as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned_len,
+        )
+        .is_ok();
+
+        let _ = CloseHandle(token);
+        queried && elevation.TokenIsElevated != 0
+    }
+}
+
+/// Returns whether the current process token is elevated (running as Administrator
+/// with UAC already satisfied).
+///
+/// Always returns `true` on non-Windows builds, since there is no equivalent
+/// elevation concept to check.
+#[cfg(not(windows))]
+pub fn is_elevated() -> bool {
+    true
+}
+
+/// Re-launches the current executable with the `runas` verb so the Service Control
+/// Manager-less console process gets a fresh, elevated UAC token.
+///
+/// `extra_args` is appended verbatim to the child's command line -- callers use
+/// this to pass along the bind address and an `--elevated-child` flag so the child
+/// knows to take over serving instead of elevating again.
+///
+/// # Returns
+/// * `Ok(())` - The elevated child process was launched. The caller should exit
+///   and let the child take over.
+/// * `Err(String)` - Elevation was declined (UAC prompt dismissed) or `ShellExecuteW`
+///   otherwise failed to launch the child.
+#[cfg(windows)]
+pub fn relaunch_elevated(extra_args: &[String]) -> Result<(), String> {
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let exe = std::env::current_exe().map_err(|e| format!("cannot locate current executable: {e}"))?;
+    let exe_hstring = HSTRING::from(exe.as_os_str());
+    let args = extra_args.join(" ");
+    let args_hstring = HSTRING::from(args.as_str());
+    let verb = HSTRING::from("runas");
+
+    // SAFETY: all string pointers are kept alive until after the call returns.
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            PCWSTR(verb.as_ptr()),
+            PCWSTR(exe_hstring.as_ptr()),
+            PCWSTR(args_hstring.as_ptr()),
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+
+    // ShellExecuteW returns a pseudo-HINSTANCE; values > 32 indicate success.
+    if (result.0 as isize) <= 32 {
+        return Err(format!(
+            "ShellExecuteW(runas) failed or was declined (code {})",
+            result.0 as isize
+        ));
+    }
+
+    Ok(())
+}
+
+/// Re-launches the current executable with the `runas` verb. Unavailable on
+/// non-Windows builds, where there is nothing to elevate.
+#[cfg(not(windows))]
+pub fn relaunch_elevated(_extra_args: &[String]) -> Result<(), String> {
+    Err("elevation is only supported on Windows".to_string())
+}
+
+/// Builds the `--elevated-child` relaunch argument list, carrying along the bind
+/// address and (if set) `--require-elevation` so the elevated child preserves the
+/// parent's intent.
+pub fn child_relaunch_args(bind_address: &str, require_elevation: bool) -> Vec<String> {
+    let mut args = vec![
+        "run-console".to_string(),
+        "--elevated-child".to_string(),
+        "--bind-address".to_string(),
+        bind_address.to_string(),
+    ];
+    if require_elevation {
+        args.push("--require-elevation".to_string());
+    }
+    args
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Remote Host Inventory
+//
+// Ordem's local machine is always queried directly via the Win32_Service WMI
+// class. This module lets the same query fan out to remote machines too, the
+// way `distant` dispatches a client command to remote servers: hosts are
+// listed in `ordem.hosts.xml` alongside `ordem.target.xml`, and each remote
+// query wraps the existing WMI command in `Invoke-Command -ComputerName`
+// instead of running it locally, so `parse_service_json` only ever has to
+// understand one JSON shape.
+
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+
+/// One remote machine Ordem can inventory/manage services on.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HostEntry {
+    pub name: String,
+    /// Name of an environment variable holding `user:password` for
+    /// `Invoke-Command -Credential`, so secrets live in the process
+    /// environment rather than in this file or in a URL. `None` runs
+    /// `Invoke-Command` with the caller's own Windows credentials.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_env: Option<String>,
+}
+
+/// Wrapper for XML (de)serialization of `ordem.hosts.xml`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct HostsConfig {
+    #[serde(rename = "Host", default)]
+    hosts: Vec<HostEntry>,
+}
+
+/// Determines the file path for the hosts configuration, alongside
+/// `ordem.target.xml`.
+///
+/// # Returns
+/// `Some(PathBuf)` with the path to `ordem.hosts.xml`, or `None` if the
+/// `LOCALAPPDATA`/`USERPROFILE` environment variables are missing.
+fn hosts_file_path() -> Option<PathBuf> {
+    env::var("LOCALAPPDATA")
+        .or_else(|_| env::var("USERPROFILE").map(|p| format!("{p}\\AppData\\Local")))
+        .ok()
+        .map(|base| PathBuf::from(base).join("Ordem").join("ordem.hosts.xml"))
+}
+
+/// Reads the configured remote hosts from `ordem.hosts.xml`.
+///
+/// Returns an empty list (rather than an error) if the file is missing or
+/// fails to parse, since an Ordem instance with no remote hosts configured
+/// is a normal, single-box setup.
+pub fn read_hosts_from_file() -> Vec<HostEntry> {
+    let Some(path) = hosts_file_path() else { return Vec::new() };
+
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| quick_xml::de::from_str::<HostsConfig>(&content).ok())
+        .map(|wrapper| wrapper.hosts)
+        .unwrap_or_default()
+}
+
+/// Finds the configured entry for `host`, if any.
+fn find_host(host: &str) -> Result<HostEntry, String> {
+    read_hosts_from_file()
+        .into_iter()
+        .find(|h| h.name == host)
+        .ok_or_else(|| format!("Host '{}' is not configured in ordem.hosts.xml", host))
+}
+
+/// Wraps `inner_command` so it runs on `host` via `Invoke-Command` instead of
+/// locally, authenticating with the credential named in `ordem.hosts.xml` for
+/// that host.
+///
+/// Looks up the credential from the environment variable named in the host's
+/// `credential_env`, rather than accepting it as a parameter, so it never
+/// passes through a URL or a log line.
+///
+/// # Returns
+/// A PowerShell command string that can be run exactly like a local command
+/// (same `pwsh`/`powershell` invocation, same JSON-shaped stdout), so callers
+/// don't need a separate remote code path.
+pub fn build_remote_command(host: &str, inner_command: &str) -> Result<String, String> {
+    let entry = find_host(host)?;
+
+    let (credential_setup, credential_arg) = match &entry.credential_env {
+        Some(var) => {
+            let raw = env::var(var).map_err(|_| {
+                format!("Environment variable '{}' (credential for host '{}') is not set", var, host)
+            })?;
+            let (user, password) = raw.split_once(':').ok_or_else(|| {
+                format!("Environment variable '{}' must be in 'user:password' format", var)
+            })?;
+            let setup = format!(
+                "$cred = New-Object System.Management.Automation.PSCredential('{}', (ConvertTo-SecureString '{}' -AsPlainText -Force)); ",
+                user.replace('\'', "''"),
+                password.replace('\'', "''"),
+            );
+            (setup, " -Credential $cred")
+        }
+        None => (String::new(), ""),
+    };
+
+    Ok(format!(
+        "{}Invoke-Command -ComputerName '{}'{} -ScriptBlock {{ {} }}",
+        credential_setup,
+        host.replace('\'', "''"),
+        credential_arg,
+        inner_command,
+    ))
+}
@@ -5,26 +5,69 @@
 // A REST API server that provides endpoints for querying Windows services
 // and managing target service configurations. Built with Actix-web.
 //
+// Every endpoint that accepts or returns services takes an optional `?host=`
+// query parameter selecting a remote machine configured in `ordem.hosts.xml`;
+// omitting it targets the local machine, as before. The exception is
+// `POST /api/apply`, which uses each submitted entry's own `host` field instead.
+//
 // # Endpoints
-// - `GET /api/services` - Retrieves all Windows services from the system
+// - `GET /api/services` - Retrieves Windows services from the system (local, or `?host=`)
+// - `GET /api/hosts` - Lists the remote hosts configured in `ordem.hosts.xml`
 // - `GET /api/targets` - Retrieves saved target configurations
 // - `POST /api/targets` - Saves target configurations
 // - `POST /api/targets-pruned` - Saves only services where start_mode differs from end_mode
+// - `POST /api/apply` - Applies start_mode changes to the system via PowerShell/sc.exe
+// - `GET /api/reconcile/status` - Reports the background reconciliation loop's state
+// - `POST /api/reconcile/enable` - Turns the reconciliation loop on (`?interval_secs=`, `?enforce=`)
+// - `POST /api/reconcile/disable` - Turns the reconciliation loop off
 // - `GET /` - Serves the frontend UI (if available)
+//
+// # Running
+// - `ordem_retrieve` / `ordem_retrieve run-console` - Foreground console process (default)
+// - `ordem_retrieve install` - Registers an auto-starting Windows service
+// - `ordem_retrieve uninstall` - Removes the registered Windows service
+// - `ordem_retrieve run` - Entry point used by the Service Control Manager; not for direct use
 
 use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder, middleware::Logger};
 use actix_cors::Cors;
 use actix_files::Files;
+use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
+use service_manager::{ServiceInstallCtx, ServiceLabel, ServiceManager, ServiceUninstallCtx};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use windows_service::service::{
+    ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_dispatcher;
+
+mod elevation;
+mod hosts;
+mod reconcile;
 
 // Configuration constants
 const BIND_ADDRESS: &str = "127.0.0.1:4000";
 const MAX_SERVICES_PAYLOAD: usize = 10_000; // Maximum services in POST payload
 
+/// Windows service name as registered with the Service Control Manager.
+const SERVICE_NAME: &str = "OrdemRetrieveService";
+
+/// File that startup diagnostics and unexpected errors are appended to when running
+/// under the Service Control Manager, since a Windows service has no console to
+/// print to.
+const SERVICE_LOG_FILE: &str = "ordem_retrieve.log";
+
+/// Whether this process currently holds an elevated (Administrator) token. Set
+/// once at startup by [`run_console`]/[`run_service_server`] and read by
+/// `GET /api/status` so the frontend can disable the "apply" button when running
+/// unprivileged.
+static ELEVATED: AtomicBool = AtomicBool::new(false);
+
 /// Represents a Windows service with its configuration and state.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct ServiceInfo {
@@ -36,6 +79,10 @@ struct ServiceInfo {
     end_mode: Option<String>,
     log_on_as: String,
     path: String,
+    /// Name of the remote host this entry was queried from/targets, as
+    /// configured in `ordem.hosts.xml`. `None` means the local machine.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
 }
 
 /// Wrapper for XML serialization of service targets.
@@ -61,6 +108,8 @@ fn targets_file_path() -> Option<PathBuf> {
 ///
 /// # Arguments
 /// * `item` - JSON value containing service information
+/// * `host` - Remote host this entry was queried from, or `None` for the
+///   local machine; stamped onto the resulting `ServiceInfo.host`.
 ///
 /// # Returns
 /// A populated `ServiceInfo` struct with normalized start mode.
@@ -70,7 +119,7 @@ fn targets_file_path() -> Option<PathBuf> {
 /// - Helper closures reduce code duplication
 /// - `unwrap_or("")` provides safe defaults for missing fields
 /// - Direct string conversion minimizes allocations
-fn parse_service_json(item: &serde_json::Value) -> ServiceInfo {
+fn parse_service_json(item: &serde_json::Value, host: Option<&str>) -> ServiceInfo {
     // Helper to extract string fields with fallback to empty string
     let get_str = |key: &str| -> String {
         item.get(key)
@@ -98,6 +147,7 @@ fn parse_service_json(item: &serde_json::Value) -> ServiceInfo {
         end_mode: None,
         log_on_as: get_str("StartName"),
         path: get_str("PathName"),
+        host: host.map(String::from),
     }
 }
 
@@ -132,11 +182,19 @@ fn normalize_start_mode(raw_mode: &str, delayed: bool) -> String {
     }
 }
 
-/// Retrieves all Windows services from the system using PowerShell WMI queries.
+/// Retrieves Windows services using PowerShell WMI queries, either from the
+/// local machine or, when `host` is set, from a remote machine configured in
+/// `ordem.hosts.xml`.
 ///
 /// Attempts to use PowerShell Core (`pwsh`) first for better performance, then
 /// falls back to Windows PowerShell (`powershell`) for compatibility with older systems.
 ///
+/// # Arguments
+/// * `host` - Name of a host from `ordem.hosts.xml` to query remotely, or
+///   `None` for the local machine. The remote case wraps the same WMI query
+///   in `Invoke-Command -ComputerName`, so the returned JSON has the same
+///   shape either way.
+///
 /// # Returns
 /// * `Ok(Vec<ServiceInfo>)` - Successfully retrieved and parsed service list
 /// * `Err(String)` - Error message if PowerShell execution or parsing failed
@@ -152,23 +210,30 @@ fn normalize_start_mode(raw_mode: &str, delayed: bool) -> String {
 ///
 /// Returns errors in these scenarios:
 /// - Not running on Windows platform
+/// - `host` is set but not configured in `ordem.hosts.xml`, or its credential
+///   environment variable is missing/malformed
 /// - PowerShell not available or execution fails
 /// - JSON response is malformed or cannot be parsed
-async fn get_services_from_system() -> Result<Vec<ServiceInfo>, String> {
+async fn get_services_from_system(host: Option<&str>) -> Result<Vec<ServiceInfo>, String> {
     // Platform check - this service only works on Windows
     if !cfg!(windows) {
         return Err("Not running on Windows (WMI queries require Windows OS)".into());
     }
 
     // PowerShell command to query all services via WMI
-    const PS_COMMAND: &str = "Get-WmiObject -Class Win32_Service | Select-Object Name, DisplayName, State, StartMode, DelayedAutoStart, StartName, PathName | ConvertTo-Json -Depth 2";
+    const WMI_QUERY: &str = "Get-WmiObject -Class Win32_Service | Select-Object Name, DisplayName, State, StartMode, DelayedAutoStart, StartName, PathName | ConvertTo-Json -Depth 2";
+
+    let ps_command = match host {
+        Some(h) => hosts::build_remote_command(h, WMI_QUERY)?,
+        None => WMI_QUERY.to_string(),
+    };
 
     // Try pwsh first (PowerShell 7+), then fall back to powershell (Windows PowerShell 5.x)
     let stdout = ["pwsh", "powershell"]
         .iter()
         .find_map(|&cmd| {
             Command::new(cmd)
-                .args(["-NoProfile", "-Command", PS_COMMAND])
+                .args(["-NoProfile", "-Command", &ps_command])
                 .output()
                 .ok()
                 .filter(|o| o.status.success())
@@ -188,13 +253,13 @@ async fn get_services_from_system() -> Result<Vec<ServiceInfo>, String> {
             // Pre-allocate with exact capacity to avoid reallocations
             let mut services = Vec::with_capacity(arr.len());
             for item in &arr {
-                services.push(parse_service_json(item));
+                services.push(parse_service_json(item, host));
             }
             services
         }
         serde_json::Value::Object(_) => {
             // Single service returned - wrap in vector
-            vec![parse_service_json(&json)]
+            vec![parse_service_json(&json, host)]
         }
         _ => {
             // Unexpected JSON type - return empty list rather than error
@@ -206,15 +271,31 @@ async fn get_services_from_system() -> Result<Vec<ServiceInfo>, String> {
     Ok(services)
 }
 
-/// API endpoint to retrieve all Windows services from the system.
+/// Query parameters accepted by endpoints that can target a remote host.
+#[derive(Debug, Deserialize)]
+struct HostQuery {
+    /// Name of a host from `ordem.hosts.xml`; omit to target the local machine.
+    #[serde(default)]
+    host: Option<String>,
+}
+
+/// API endpoint to retrieve Windows services from the system (local, or a
+/// remote host selected via `?host=`).
 #[get("/api/services")]
-async fn api_services() -> impl Responder {
-    match get_services_from_system().await {
+async fn api_services(query: web::Query<HostQuery>) -> impl Responder {
+    match get_services_from_system(query.host.as_deref()).await {
         Ok(list) => HttpResponse::Ok().json(list),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
+/// API endpoint to list the remote hosts configured in `ordem.hosts.xml`, for
+/// the frontend to populate a host picker.
+#[get("/api/hosts")]
+async fn api_hosts() -> impl Responder {
+    HttpResponse::Ok().json(hosts::read_hosts_from_file())
+}
+
 /// Reads target configurations from the XML file.
 ///
 /// # Arguments
@@ -253,25 +334,52 @@ fn write_targets_to_file(path: &PathBuf, services: &[ServiceInfo]) -> std::io::R
     fs::write(path, xml.as_bytes())
 }
 
-/// API endpoint to retrieve saved target configurations.
+/// Reads the targets for a single `host` (`None` for the local machine) out
+/// of the shared targets file, which holds entries for every host keyed by
+/// their `host` field.
+fn read_targets_for_host(path: &PathBuf, host: Option<&str>) -> Option<Vec<ServiceInfo>> {
+    read_targets_from_file(path).map(|all| {
+        all.into_iter().filter(|s| s.host.as_deref() == host).collect()
+    })
+}
+
+/// Replaces the targets for a single `host` (`None` for the local machine) in
+/// the shared targets file, leaving every other host's entries untouched.
+///
+/// This is what lets one `ordem.target.xml` hold targets for the whole fleet
+/// instead of just the local machine: entries are keyed by their `host`
+/// field rather than the file being split per host.
+fn write_targets_for_host(path: &PathBuf, host: Option<&str>, services: &[ServiceInfo]) -> std::io::Result<()> {
+    let mut all = read_targets_from_file(path).unwrap_or_default();
+    all.retain(|s| s.host.as_deref() != host);
+    all.extend(services.iter().cloned().map(|mut s| {
+        s.host = host.map(String::from);
+        s
+    }));
+    write_targets_to_file(path, &all)
+}
+
+/// API endpoint to retrieve saved target configurations for the local
+/// machine, or a remote host selected via `?host=`.
 /// Initializes the file with current system services if it doesn't exist.
 #[get("/api/targets")]
-async fn api_get_targets() -> impl Responder {
+async fn api_get_targets(query: web::Query<HostQuery>) -> impl Responder {
     let Some(path) = targets_file_path() else {
         return HttpResponse::InternalServerError().body("Could not determine targets file path");
     };
+    let host = query.host.as_deref();
 
     if path.exists() {
-        return match read_targets_from_file(&path) {
+        return match read_targets_for_host(&path, host) {
             Some(list) => HttpResponse::Ok().json(list),
             None => HttpResponse::InternalServerError().body("Failed to parse existing target file"),
         };
     }
 
     // Initialize file with current services
-    match get_services_from_system().await {
+    match get_services_from_system(host).await {
         Ok(list) => {
-            if let Err(e) = write_targets_to_file(&path, &list) {
+            if let Err(e) = write_targets_for_host(&path, host, &list) {
                 HttpResponse::InternalServerError().body(format!("Failed to write initial target file: {}", e))
             } else {
                 HttpResponse::Ok().json(list)
@@ -281,9 +389,10 @@ async fn api_get_targets() -> impl Responder {
     }
 }
 
-/// API endpoint to save target configurations.
+/// API endpoint to save target configurations for the local machine, or a
+/// remote host selected via `?host=`.
 #[post("/api/targets")]
-async fn api_post_targets(body: web::Json<Vec<ServiceInfo>>) -> impl Responder {
+async fn api_post_targets(body: web::Json<Vec<ServiceInfo>>, query: web::Query<HostQuery>) -> impl Responder {
     // Validate payload size to prevent excessive memory usage
     if body.len() > MAX_SERVICES_PAYLOAD {
         return HttpResponse::BadRequest().body(format!(
@@ -297,16 +406,17 @@ async fn api_post_targets(body: web::Json<Vec<ServiceInfo>>) -> impl Responder {
         return HttpResponse::InternalServerError().body("Could not determine targets file path");
     };
 
-    match write_targets_to_file(&path, &body) {
+    match write_targets_for_host(&path, query.host.as_deref(), &body) {
         Ok(_) => HttpResponse::Ok().body("saved"),
         Err(e) => HttpResponse::InternalServerError().body(format!("write error: {}", e)),
     }
 }
 
-/// API endpoint to save pruned target configurations.
+/// API endpoint to save pruned target configurations for the local machine,
+/// or a remote host selected via `?host=`.
 /// Only saves services where start_mode differs from end_mode.
 #[post("/api/targets-pruned")]
-async fn api_post_targets_pruned(body: web::Json<Vec<ServiceInfo>>) -> impl Responder {
+async fn api_post_targets_pruned(body: web::Json<Vec<ServiceInfo>>, query: web::Query<HostQuery>) -> impl Responder {
     // Validate payload size to prevent excessive memory usage
     if body.len() > MAX_SERVICES_PAYLOAD {
         return HttpResponse::BadRequest().body(format!(
@@ -332,244 +442,663 @@ async fn api_post_targets_pruned(body: web::Json<Vec<ServiceInfo>>) -> impl Resp
         .cloned()
         .collect();
 
-    match write_targets_to_file(&path, &pruned_services) {
+    match write_targets_for_host(&path, query.host.as_deref(), &pruned_services) {
         Ok(_) => HttpResponse::Ok().body("saved"),
         Err(e) => HttpResponse::InternalServerError().body(format!("write error: {}", e)),
     }
 }
 
-#[actix_web::main]
-async fn main() -> std::io::Result<()> {
+/// Query parameters accepted by `POST /api/apply`.
+#[derive(Debug, Deserialize)]
+struct ApplyQuery {
+    /// When `true`, report the planned commands without running them.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Outcome of attempting to apply one service's desired start mode.
+#[derive(Debug, Serialize)]
+struct ApplyResult {
+    name: String,
+    requested_mode: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// The command that was (or, for `?dry_run=true`, would have been) run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+}
+
+/// The underlying system command needed to move a service to a given start mode.
+///
+/// `Set-Service -StartupType` covers `Automatic`/`Manual`/`Disabled`, but it cannot
+/// set the delayed-auto-start flag, so `"Automatic (Delayed Start)"` is instead
+/// applied through `sc.exe config ... start= delayed-auto`.
+enum ApplyCommand {
+    /// A `Set-Service` invocation, run through the same `pwsh`/`powershell` fallback
+    /// used in [`get_services_from_system`].
+    SetService(String),
+    /// An `sc.exe config` invocation.
+    ScConfig(Vec<String>),
+}
+
+impl ApplyCommand {
+    /// Human-readable rendering of the command, for `?dry_run=true` responses.
+    fn describe(&self) -> String {
+        match self {
+            ApplyCommand::SetService(ps) => ps.clone(),
+            ApplyCommand::ScConfig(args) => format!("sc.exe {}", args.join(" ")),
+        }
+    }
+
+    /// Executes the command.
+    ///
+    /// # Returns
+    /// `Ok(())` if the command ran and exited successfully, `Err(String)` with the
+    /// captured error output otherwise.
+    fn run(&self) -> Result<(), String> {
+        match self {
+            ApplyCommand::SetService(ps_command) => {
+                let mut spawn_error = None;
+                for cmd in ["pwsh", "powershell"] {
+                    match Command::new(cmd).args(["-NoProfile", "-Command", ps_command]).output() {
+                        Ok(output) if output.status.success() => return Ok(()),
+                        Ok(output) => {
+                            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+                        }
+                        Err(_) => spawn_error = Some(cmd),
+                    }
+                }
+                Err(format!(
+                    "Failed to run PowerShell (tried pwsh and powershell): last attempt was {}",
+                    spawn_error.unwrap_or("powershell")
+                ))
+            }
+            ApplyCommand::ScConfig(args) => {
+                let output = Command::new("sc.exe")
+                    .args(args)
+                    .output()
+                    .map_err(|e| format!("Failed to run sc.exe: {}", e))?;
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    Err(String::from_utf8_lossy(&output.stdout).trim().to_string())
+                }
+            }
+        }
+    }
+}
+
+/// Builds the command needed to move service `name` to `mode` on `host` (or the
+/// local machine, if `host` is `None`).
+///
+/// # Errors
+/// Returns `Err` if `mode` is not one of the recognized start modes, or if
+/// `host` is set but not configured in `ordem.hosts.xml` (or its credential
+/// environment variable is missing/malformed).
+fn build_apply_command(name: &str, mode: &str, host: Option<&str>) -> Result<ApplyCommand, String> {
+    match mode {
+        "Automatic" | "Manual" | "Disabled" => {
+            let ps = format!("Set-Service -Name '{}' -StartupType {}", name, mode);
+            let ps = match host {
+                Some(h) => hosts::build_remote_command(h, &ps)?,
+                None => ps,
+            };
+            Ok(ApplyCommand::SetService(ps))
+        }
+        "Automatic (Delayed Start)" => {
+            // sc.exe natively supports a remote machine via a leading "\\host" argument.
+            let mut args = Vec::new();
+            if let Some(h) = host {
+                args.push(format!("\\\\{}", h));
+            }
+            args.extend([
+                "config".to_string(),
+                name.to_string(),
+                "start=".to_string(),
+                "delayed-auto".to_string(),
+            ]);
+            Ok(ApplyCommand::ScConfig(args))
+        }
+        _ => Err("Unsupported start mode".to_string()),
+    }
+}
+
+/// API endpoint that enforces the desired start mode for each service whose
+/// `end_mode` differs from its `start_mode`.
+///
+/// Accepts the same `Vec<ServiceInfo>` payload as `/api/targets-pruned`, computes
+/// the same pruned set, and issues a `Set-Service`/`sc.exe` command per service.
+/// Pass `?dry_run=true` to see the planned commands without executing them. Apart
+/// from rejecting an oversized payload with `400 Bad Request`, returns `200 OK` with a
+/// per-service result array so the caller can see partial failures instead of a
+/// single opaque 500.
+#[post("/api/apply")]
+async fn api_apply(body: web::Json<Vec<ServiceInfo>>, query: web::Query<ApplyQuery>) -> impl Responder {
+    if body.len() > MAX_SERVICES_PAYLOAD {
+        return HttpResponse::BadRequest().body(format!(
+            "Payload too large: {} services (max: {})",
+            body.len(),
+            MAX_SERVICES_PAYLOAD
+        ));
+    }
+
+    let pruned = body.iter().filter(|service| {
+        service.end_mode
+            .as_ref()
+            .map(|end| end != &service.start_mode)
+            .unwrap_or(false)
+    });
+
+    let mut results = Vec::new();
+    for service in pruned {
+        let requested_mode = service.end_mode.clone().unwrap_or_default();
+
+        let command = match build_apply_command(&service.name, &requested_mode, service.host.as_deref()) {
+            Ok(command) => command,
+            Err(e) => {
+                results.push(ApplyResult {
+                    name: service.name.clone(),
+                    requested_mode,
+                    success: false,
+                    error: Some(e),
+                    command: None,
+                });
+                continue;
+            }
+        };
+
+        if query.dry_run {
+            results.push(ApplyResult {
+                name: service.name.clone(),
+                requested_mode,
+                success: true,
+                error: None,
+                command: Some(command.describe()),
+            });
+            continue;
+        }
+
+        results.push(match command.run() {
+            Ok(()) => ApplyResult {
+                name: service.name.clone(),
+                requested_mode,
+                success: true,
+                error: None,
+                command: Some(command.describe()),
+            },
+            Err(e) => ApplyResult {
+                name: service.name.clone(),
+                requested_mode,
+                success: false,
+                error: Some(e),
+                command: Some(command.describe()),
+            },
+        });
+    }
+
+    HttpResponse::Ok().json(results)
+}
+
+/// Elevation status reported by `GET /api/status`.
+#[derive(Debug, Serialize)]
+struct StatusResponse {
+    /// Whether this process holds an elevated (Administrator) token. When `false`,
+    /// `/api/apply` will fail with access-denied for any `Set-Service`/`sc config`
+    /// call, so the UI should disable the "apply" button.
+    elevated: bool,
+}
+
+/// API endpoint reporting whether the backend is running elevated, so the
+/// frontend can disable the "apply" button when it is not.
+#[get("/api/status")]
+async fn api_status() -> impl Responder {
+    HttpResponse::Ok().json(StatusResponse { elevated: ELEVATED.load(Ordering::SeqCst) })
+}
+
+/// Query parameters accepted by `POST /api/reconcile/enable`.
+#[derive(Debug, Deserialize)]
+struct ReconcileEnableQuery {
+    /// Seconds between reconciliation passes; leave unset to keep the current interval.
+    #[serde(default)]
+    interval_secs: Option<u64>,
+    /// `true` to re-apply drifted services, `false` to only log/count them (report-only);
+    /// leave unset to keep the current behavior.
+    #[serde(default)]
+    enforce: Option<bool>,
+}
+
+/// API endpoint reporting the background reconciliation loop's state: whether
+/// it's enabled, enforce vs report-only, its interval, and counters from the
+/// most recent pass.
+#[get("/api/reconcile/status")]
+async fn api_reconcile_status() -> impl Responder {
+    HttpResponse::Ok().json(reconcile::status())
+}
+
+/// API endpoint that turns the reconciliation loop on, optionally updating
+/// its interval and enforce/report-only behavior via query parameters.
+#[post("/api/reconcile/enable")]
+async fn api_reconcile_enable(query: web::Query<ReconcileEnableQuery>) -> impl Responder {
+    reconcile::configure(true, query.interval_secs, query.enforce);
+    HttpResponse::Ok().json(reconcile::status())
+}
+
+/// API endpoint that turns the reconciliation loop off.
+#[post("/api/reconcile/disable")]
+async fn api_reconcile_disable() -> impl Responder {
+    reconcile::configure(false, None, None);
+    HttpResponse::Ok().json(reconcile::status())
+}
+
+/// Command-line interface for running Ordem as a foreground process or as an
+/// installable Windows service managed by the Service Control Manager.
+#[derive(Parser)]
+#[command(name = "ordem_retrieve", about = "Ordem Service Retrieval Backend")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<ServiceCommand>,
+}
+
+/// Subcommands for managing the Windows service registration.
+#[derive(Subcommand)]
+enum ServiceCommand {
+    /// Registers this executable as an auto-starting Windows service.
+    Install,
+    /// Removes the registered Windows service.
+    Uninstall,
+    /// Entry point invoked by the Service Control Manager; not for direct use.
+    Run,
+    /// Runs in the foreground as a console process (default when no subcommand is given).
+    RunConsole {
+        /// Address to bind the HTTP server to.
+        #[arg(long, default_value = BIND_ADDRESS)]
+        bind_address: String,
+        /// Fail the startup diagnostics immediately, with a clear message, if not
+        /// running elevated instead of continuing with `/api/apply` disabled.
+        #[arg(long)]
+        require_elevation: bool,
+        /// Set on the relaunched, elevated child process; not for direct use.
+        #[arg(long, hide = true)]
+        elevated_child: bool,
+    },
+}
+
+fn main() -> std::io::Result<()> {
     // Initialize logger early to capture all diagnostics
     env_logger::init();
 
-    println!("========================================");
-    println!("Ordem Service Retrieval Backend");
-    println!("========================================");
-    println!();
+    let default_command = ServiceCommand::RunConsole {
+        bind_address: BIND_ADDRESS.to_string(),
+        require_elevation: false,
+        elevated_child: false,
+    };
 
-    // === STARTUP DIAGNOSTICS ===
-    println!("[DIAGNOSTICS] Running startup checks...");
-    println!();
+    match Cli::parse().command.unwrap_or(default_command) {
+        ServiceCommand::Install => {
+            install_service().map_err(|e| {
+                eprintln!("ERROR: Failed to install service '{}': {}", SERVICE_NAME, e);
+                e
+            })?;
+            println!("Service '{}' installed (auto-start).", SERVICE_NAME);
+            Ok(())
+        }
+        ServiceCommand::Uninstall => {
+            uninstall_service().map_err(|e| {
+                eprintln!("ERROR: Failed to uninstall service '{}': {}", SERVICE_NAME, e);
+                e
+            })?;
+            println!("Service '{}' uninstalled.", SERVICE_NAME);
+            Ok(())
+        }
+        ServiceCommand::Run => {
+            if let Err(e) = service_dispatcher::start(SERVICE_NAME, service_main) {
+                eprintln!("ERROR: Failed to start service dispatcher: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        ServiceCommand::RunConsole { bind_address, require_elevation, elevated_child } => {
+            actix_web::rt::System::new().block_on(run_console(bind_address, require_elevation, elevated_child))
+        }
+    }
+}
+
+/// Registers this executable with the Service Control Manager as an auto-starting
+/// service, pointed at `<exe> run`, so administrators can manage Ordem the same way
+/// they manage the services it inspects.
+fn install_service() -> std::io::Result<()> {
+    let manager = <dyn ServiceManager>::native()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("no native service manager available: {}", e)))?;
+
+    let label: ServiceLabel = SERVICE_NAME
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{}", e)))?;
+
+    manager
+        .install(ServiceInstallCtx {
+            label,
+            program: env::current_exe()?,
+            args: vec!["run".into()],
+            contents: None,
+            username: None,
+            working_directory: env::current_dir().ok(),
+            environment: None,
+            autostart: true,
+            disable_restart_on_failure: false,
+        })
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+}
+
+/// Removes the Windows service registered by [`install_service`].
+fn uninstall_service() -> std::io::Result<()> {
+    let manager = <dyn ServiceManager>::native()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("no native service manager available: {}", e)))?;
+
+    let label: ServiceLabel = SERVICE_NAME
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{}", e)))?;
+
+    manager.uninstall(ServiceUninstallCtx { label }).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+}
+
+/// Windows service entry point called by the Service Control Manager.
+///
+/// Registers a control handler for `Stop`/`Shutdown`, signals the service as
+/// running, runs the Actix server until asked to stop, then signals stopped.
+/// Startup diagnostics and errors go to [`SERVICE_LOG_FILE`] instead of stdout,
+/// since a Windows service has no console attached.
+extern "system" fn service_main(_argc: u32, _argv: *mut *mut u16) {
+    let (stop_tx, stop_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+    let status_handle = match service_control_handler::register(SERVICE_NAME, move |event| match event {
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            let _ = stop_tx.send(());
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    }) {
+        Ok(handle) => handle,
+        Err(e) => {
+            log_to_service_file(&format!("Failed to register service control handler: {}", e));
+            return;
+        }
+    };
+
+    let set_status = |state: ServiceState, controls_accepted: ServiceControlAccept| {
+        let _ = status_handle.set_service_status(ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::from_secs(10),
+            process_id: None,
+        });
+    };
+
+    set_status(ServiceState::Running, ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN);
+
+    if let Err(e) = actix_web::rt::System::new().block_on(run_service_server(stop_rx)) {
+        log_to_service_file(&format!("Service worker error: {}", e));
+    }
+
+    set_status(ServiceState::Stopped, ServiceControlAccept::empty());
+}
+
+/// Appends a line to [`SERVICE_LOG_FILE`], used in place of `println!`/`eprintln!`
+/// when running under the Service Control Manager.
+fn log_to_service_file(message: &str) {
+    use std::io::Write as _;
+
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(SERVICE_LOG_FILE) {
+        let _ = writeln!(file, "[{}] {}", secs, message);
+    }
+}
+
+/// Runs the HTTP server under the Service Control Manager and stops gracefully once
+/// a value arrives on `stop_rx` (sent by the [`service_main`] control handler on
+/// `Stop`/`Shutdown`).
+async fn run_service_server(mut stop_rx: tokio::sync::mpsc::UnboundedReceiver<()>) -> std::io::Result<()> {
+    if let Err(e) = run_startup_diagnostics(&log_to_service_file, BIND_ADDRESS).await {
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
+    }
+
+    ELEVATED.store(elevation::is_elevated(), Ordering::SeqCst);
+
+    let ui_path = find_ui_dist();
+    let server = build_server(ui_path, BIND_ADDRESS)?;
+    let handle = server.handle();
+    let server_task = actix_web::rt::spawn(server);
+
+    stop_rx.recv().await;
+    handle.stop(true).await;
+    let _ = server_task.await;
+
+    Ok(())
+}
+
+/// Runs the 6-step startup diagnostic checks (platform, PowerShell, service query,
+/// config directory, write test, port availability), emitting progress through
+/// `log`. Shared by console mode (logs to stdout) and service mode (logs to
+/// [`SERVICE_LOG_FILE`]), so the checks themselves stay identical in both contexts.
+///
+/// # Returns
+/// `Ok(targets_path)` if every check passes, `Err(message)` describing the first
+/// failed check.
+async fn run_startup_diagnostics(log: &impl Fn(&str), bind_address: &str) -> Result<PathBuf, String> {
+    log("========================================");
+    log("Ordem Service Retrieval Backend");
+    log("========================================");
+    log("");
+    log("[DIAGNOSTICS] Running startup checks...");
+    log("");
 
     // 1. Platform check
-    print!("[CHECK 1/6] Platform verification... ");
     if !cfg!(windows) {
-        eprintln!("FAILED");
-        eprintln!();
-        eprintln!("ERROR: This service requires Windows OS");
-        eprintln!("Current platform is not Windows.");
-        std::process::exit(1);
+        log("[CHECK 1/6] Platform verification... FAILED");
+        return Err("This service requires Windows OS\nCurrent platform is not Windows.".to_string());
     }
-    println!("OK (Windows)");
+    log("[CHECK 1/6] Platform verification... OK (Windows)");
 
     // 2. PowerShell availability
-    print!("[CHECK 2/6] PowerShell availability... ");
-    let ps_available = ["pwsh", "powershell"]
-        .iter()
-        .find(|&&cmd| {
-            Command::new(cmd)
-                .args(["-NoProfile", "-Command", "exit 0"])
-                .output()
-                .map(|o| o.status.success())
-                .unwrap_or(false)
-        });
-
+    let ps_available = ["pwsh", "powershell"].iter().find(|&&cmd| {
+        Command::new(cmd)
+            .args(["-NoProfile", "-Command", "exit 0"])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    });
     match ps_available {
-        Some(cmd) => println!("OK ({} found)", cmd),
+        Some(cmd) => log(&format!("[CHECK 2/6] PowerShell availability... OK ({} found)", cmd)),
         None => {
-            eprintln!("FAILED");
-            eprintln!();
-            eprintln!("ERROR: PowerShell is required but not found");
-            eprintln!("The service needs PowerShell to query Windows services.");
-            eprintln!("Please ensure PowerShell is installed and in PATH.");
-            std::process::exit(1);
+            log("[CHECK 2/6] PowerShell availability... FAILED");
+            return Err(
+                "PowerShell is required but not found\nThe service needs PowerShell to query \
+                 Windows services.\nPlease ensure PowerShell is installed and in PATH."
+                    .to_string(),
+            );
         }
     }
 
     // 3. Service query test
-    print!("[CHECK 3/6] Windows service query test... ");
-    match get_services_from_system().await {
-        Ok(services) => println!("OK ({} services found)", services.len()),
+    match get_services_from_system(None).await {
+        Ok(services) => {
+            log(&format!("[CHECK 3/6] Windows service query test... OK ({} services found)", services.len()))
+        }
         Err(e) => {
-            eprintln!("FAILED");
-            eprintln!();
-            eprintln!("ERROR: Cannot query Windows services");
-            eprintln!("Details: {}", e);
-            eprintln!();
-            eprintln!("This may indicate:");
-            eprintln!("  - Insufficient permissions to query WMI");
-            eprintln!("  - PowerShell execution policy restrictions");
-            eprintln!("  - WMI service is not running");
-            std::process::exit(1);
+            log("[CHECK 3/6] Windows service query test... FAILED");
+            return Err(format!(
+                "Cannot query Windows services\nDetails: {}\n\nThis may indicate:\n  \
+                 - Insufficient permissions to query WMI\n  \
+                 - PowerShell execution policy restrictions\n  \
+                 - WMI service is not running",
+                e
+            ));
         }
     }
 
     // 4. Configuration directory
-    print!("[CHECK 4/6] Configuration directory... ");
     let targets_path = match targets_file_path() {
         Some(p) => {
-            println!("OK");
-            println!("              Path: {}", p.display());
+            log(&format!("[CHECK 4/6] Configuration directory... OK (path: {})", p.display()));
             p
         }
         None => {
-            eprintln!("FAILED");
-            eprintln!();
-            eprintln!("ERROR: Cannot determine configuration file path");
-            eprintln!("Missing environment variables: LOCALAPPDATA or USERPROFILE");
-            std::process::exit(1);
+            log("[CHECK 4/6] Configuration directory... FAILED");
+            return Err(
+                "Cannot determine configuration file path\nMissing environment variables: \
+                 LOCALAPPDATA or USERPROFILE"
+                    .to_string(),
+            );
         }
     };
 
     // 5. Configuration write test
-    print!("[CHECK 5/6] Configuration write test... ");
     if let Some(parent) = targets_path.parent() {
-        match fs::create_dir_all(parent) {
+        if let Err(e) = fs::create_dir_all(parent) {
+            log("[CHECK 5/6] Configuration write test... FAILED");
+            return Err(format!(
+                "Cannot create configuration directory\nPath: {}\nDetails: {}",
+                parent.display(),
+                e
+            ));
+        }
+
+        // Test write permissions with a temp file
+        let test_file = parent.join(".ordem_write_test");
+        match fs::write(&test_file, b"test") {
             Ok(_) => {
-                // Test write permissions with a temp file
-                let test_file = parent.join(".ordem_write_test");
-                match fs::write(&test_file, b"test") {
-                    Ok(_) => {
-                        let _ = fs::remove_file(&test_file);
-                        println!("OK (writable)");
-                    }
-                    Err(e) => {
-                        eprintln!("FAILED");
-                        eprintln!();
-                        eprintln!("ERROR: Cannot write to configuration directory");
-                        eprintln!("Path: {}", parent.display());
-                        eprintln!("Details: {}", e);
-                        eprintln!();
-                        eprintln!("Check folder permissions and disk space.");
-                        std::process::exit(1);
-                    }
-                }
+                let _ = fs::remove_file(&test_file);
+                log("[CHECK 5/6] Configuration write test... OK (writable)");
             }
             Err(e) => {
-                eprintln!("FAILED");
-                eprintln!();
-                eprintln!("ERROR: Cannot create configuration directory");
-                eprintln!("Path: {}", parent.display());
-                eprintln!("Details: {}", e);
-                std::process::exit(1);
+                log("[CHECK 5/6] Configuration write test... FAILED");
+                return Err(format!(
+                    "Cannot write to configuration directory\nPath: {}\nDetails: {}\n\n\
+                     Check folder permissions and disk space.",
+                    parent.display(),
+                    e
+                ));
             }
         }
     } else {
-        println!("SKIPPED (no parent)");
+        log("[CHECK 5/6] Configuration write test... SKIPPED (no parent)");
     }
 
     // 6. Port availability
-    print!("[CHECK 6/6] Port availability ({})... ", BIND_ADDRESS);
-    match std::net::TcpListener::bind(BIND_ADDRESS) {
+    match std::net::TcpListener::bind(bind_address) {
         Ok(listener) => {
             drop(listener); // Release the port immediately
-            println!("OK (available)");
+            log(&format!("[CHECK 6/6] Port availability ({})... OK (available)", bind_address));
         }
         Err(e) => {
-            eprintln!("FAILED");
-            eprintln!();
-            eprintln!("ERROR: Cannot bind to {}", BIND_ADDRESS);
-            eprintln!("Details: {}", e);
-            eprintln!();
-            eprintln!("Possible causes:");
-            eprintln!("  - Port 4000 is already in use by another process");
-            eprintln!("  - Firewall is blocking the port");
-            eprintln!("  - Another instance of ordem_service is running");
-            eprintln!();
-            eprintln!("To find what's using the port, run:");
-            eprintln!("  netstat -ano | findstr :4000");
-            std::process::exit(1);
+            log("[CHECK 6/6] Port availability... FAILED");
+            return Err(format!(
+                "Cannot bind to {}\nDetails: {}\n\nPossible causes:\n  \
+                 - The port is already in use by another process\n  \
+                 - Firewall is blocking the port\n  \
+                 - Another instance of ordem_service is running\n\n\
+                 To find what's using the port, run:\n  netstat -ano | findstr :{}",
+                bind_address,
+                e,
+                bind_address.rsplit(':').next().unwrap_or(bind_address)
+            ));
         }
     }
 
-    println!();
-    println!("[DIAGNOSTICS] All startup checks passed!");
-    println!("========================================");
-    println!();
+    log("");
+    log("[DIAGNOSTICS] All startup checks passed!");
+    log("========================================");
+    log("");
 
-    /// Attempts to locate the built frontend UI distribution folder.
-    ///
-    /// Searches multiple common locations relative to both the current directory
-    /// and the executable location. This allows the server to find the UI whether
-    /// run from the project root during development or from the installed location.
-    ///
-    /// # Search Order
-    ///
-    /// For each path pattern, checks both:
-    /// 1. Relative to current working directory
-    /// 2. Relative to executable directory
-    ///
-    /// Path patterns searched:
-    /// - `dist/ui` - Standard build output location
-    /// - `../dist/ui` - When running from subdirectory
-    /// - `../../dist/ui` - When running from nested subdirectory
-    /// - `ui/dist` - Alternative build location
-    /// - `../ui/dist` - When UI is sibling directory
-    ///
-    /// # Returns
-    /// * `Some(PathBuf)` - Path to the UI distribution folder if found
-    /// * `None` - UI folder not found in any searched location
-    ///
-    /// # Performance Notes
-    ///
-    /// - Early returns on first match (short-circuit evaluation)
-    /// - Filesystem checks are relatively expensive but unavoidable
-    fn find_ui_dist() -> Option<PathBuf> {
-        let cwd = env::current_dir().ok()?;
-        let exe_dir = env::current_exe()
-            .ok()
-            .and_then(|p| p.parent().map(|d| d.to_path_buf()));
-
-        // Common UI distribution folder locations (in priority order)
-        const PATHS: &[&str] = &[
-            "dist/ui",      // Standard build output
-            "../dist/ui",   // Run from subdirectory
-            "../../dist/ui",// Run from nested subdirectory
-            "ui/dist",      // Alternative location
-            "../ui/dist",   // UI as sibling
-        ];
-
-        // Search each path in both current directory and executable directory
-        PATHS
-            .iter()
-            .flat_map(|&p| {
-                let mut candidates = vec![cwd.join(p)];
-                if let Some(ref exe) = exe_dir {
-                    candidates.push(exe.join(p));
-                }
-                candidates
-            })
-            .find(|c| c.exists() && c.is_dir())
-    }
+    Ok(targets_path)
+}
 
-    let ui_path = find_ui_dist();
+/// Attempts to locate the built frontend UI distribution folder.
+///
+/// Searches multiple common locations relative to both the current directory
+/// and the executable location. This allows the server to find the UI whether
+/// run from the project root during development or from the installed location.
+///
+/// # Search Order
+///
+/// For each path pattern, checks both:
+/// 1. Relative to current working directory
+/// 2. Relative to executable directory
+///
+/// Path patterns searched:
+/// - `dist/ui` - Standard build output location
+/// - `../dist/ui` - When running from subdirectory
+/// - `../../dist/ui` - When running from nested subdirectory
+/// - `ui/dist` - Alternative build location
+/// - `../ui/dist` - When UI is sibling directory
+///
+/// # Returns
+/// * `Some(PathBuf)` - Path to the UI distribution folder if found
+/// * `None` - UI folder not found in any searched location
+///
+/// # Performance Notes
+///
+/// - Early returns on first match (short-circuit evaluation)
+/// - Filesystem checks are relatively expensive but unavoidable
+fn find_ui_dist() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.to_path_buf()));
 
-    if let Some(ref p) = ui_path {
-        println!("Backend:  http://{}", BIND_ADDRESS);
-        println!("Frontend: http://{} (served from: {})", BIND_ADDRESS, p.display());
-        println!("Mode:     Integrated (single endpoint)");
-    } else {
-        println!("Backend:  http://{}", BIND_ADDRESS);
-        println!("Frontend: NOT FOUND");
-        println!("Mode:     API-only (no UI)");
-        println!();
-        println!("To enable UI, build it first:");
-        println!("  ./scripts/build-all.ps1");
-    }
-    println!("========================================");
-    println!();
+    // Common UI distribution folder locations (in priority order)
+    const PATHS: &[&str] = &[
+        "dist/ui",      // Standard build output
+        "../dist/ui",   // Run from subdirectory
+        "../../dist/ui",// Run from nested subdirectory
+        "ui/dist",      // Alternative location
+        "../ui/dist",   // UI as sibling
+    ];
+
+    // Search each path in both current directory and executable directory
+    PATHS
+        .iter()
+        .flat_map(|&p| {
+            let mut candidates = vec![cwd.join(p)];
+            if let Some(ref exe) = exe_dir {
+                candidates.push(exe.join(p));
+            }
+            candidates
+        })
+        .find(|c| c.exists() && c.is_dir())
+}
+
+/// Builds the Actix app (routes, CORS, logging, optional frontend static files)
+/// bound to `bind_address`. Shared by console mode and service mode so the two
+/// only differ in how they report diagnostics and stop.
+///
+/// Also spawns the background reconciliation loop (see [`reconcile`]), since
+/// this is the one place both modes route through before serving.
+fn build_server(ui_path: Option<PathBuf>, bind_address: &str) -> std::io::Result<actix_web::dev::Server> {
+    actix_web::rt::spawn(reconcile::run_loop());
 
-    // Start HTTP server with enhanced error handling
-    print!("[STARTUP] Binding to {}... ", BIND_ADDRESS);
-    let server = HttpServer::new(move || {
+    Ok(HttpServer::new(move || {
         let mut app = App::new()
             .wrap(Cors::permissive())
             .wrap(Logger::default())
             .service(api_services)
+            .service(api_hosts)
             .service(api_get_targets)
             .service(api_post_targets)
-            .service(api_post_targets_pruned);
+            .service(api_post_targets_pruned)
+            .service(api_apply)
+            .service(api_status)
+            .service(api_reconcile_status)
+            .service(api_reconcile_enable)
+            .service(api_reconcile_disable);
 
         if let Some(ref p) = ui_path {
             app = app.service(Files::new("/", p).index_file("index.html"));
@@ -579,11 +1108,71 @@ async fn main() -> std::io::Result<()> {
 
         app
     })
-    .bind(BIND_ADDRESS)
-    .map_err(|e| {
+    .bind(bind_address)?
+    .run())
+}
+
+/// Runs the backend in the foreground as a console process: self-elevates via UAC
+/// if needed, prints startup diagnostics to stdout, then serves the API (and
+/// frontend, if built) until interrupted.
+async fn run_console(bind_address: String, require_elevation: bool, elevated_child: bool) -> std::io::Result<()> {
+    let elevated = elevation::is_elevated();
+
+    if !elevated && !elevated_child {
+        println!("[ELEVATION] Not running elevated; requesting UAC relaunch...");
+        let relaunch_args = elevation::child_relaunch_args(&bind_address, require_elevation);
+        match elevation::relaunch_elevated(&relaunch_args) {
+            Ok(()) => {
+                println!("[ELEVATION] Elevated instance launched; exiting this process.");
+                return Ok(());
+            }
+            Err(e) => {
+                println!("[ELEVATION] {}", e);
+                if require_elevation {
+                    eprintln!();
+                    eprintln!("ERROR: --require-elevation was set and elevation was declined or failed.");
+                    eprintln!("Re-run as Administrator, or omit --require-elevation to continue unprivileged");
+                    eprintln!("(the /api/apply endpoint will fail and the UI should disable it).");
+                    std::process::exit(1);
+                }
+                println!("[ELEVATION] Continuing unprivileged; /api/apply will fail and the UI should disable it.");
+            }
+        }
+    }
+    ELEVATED.store(elevated, Ordering::SeqCst);
+
+    let _targets_path = match run_startup_diagnostics(&|msg| println!("{}", msg), &bind_address).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!();
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let ui_path = find_ui_dist();
+
+    if let Some(ref p) = ui_path {
+        println!("Backend:  http://{}", bind_address);
+        println!("Frontend: http://{} (served from: {})", bind_address, p.display());
+        println!("Mode:     Integrated (single endpoint)");
+    } else {
+        println!("Backend:  http://{}", bind_address);
+        println!("Frontend: NOT FOUND");
+        println!("Mode:     API-only (no UI)");
+        println!();
+        println!("To enable UI, build it first:");
+        println!("  ./scripts/build-all.ps1");
+    }
+    println!("Elevated: {}", elevated);
+    println!("========================================");
+    println!();
+
+    print!("[STARTUP] Binding to {}... ", bind_address);
+    let server = build_server(ui_path, &bind_address).map_err(|e| {
         eprintln!("FAILED");
         eprintln!();
-        eprintln!("ERROR: Failed to bind HTTP server to {}", BIND_ADDRESS);
+        eprintln!("ERROR: Failed to bind HTTP server to {}", bind_address);
         eprintln!("Details: {}", e);
         eprintln!();
         eprintln!("This is unexpected since port availability was verified.");
@@ -597,7 +1186,7 @@ async fn main() -> std::io::Result<()> {
     println!("Server is running. Press Ctrl+C to stop.");
     println!();
 
-    server.run().await.map_err(|e| {
+    server.await.map_err(|e| {
         eprintln!();
         eprintln!("========================================");
         eprintln!("ERROR: Server stopped unexpectedly");
@@ -0,0 +1,203 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Target Reconciliation Loop
+//
+// Saving targets to `ordem.target.xml` only records *intent*; something has
+// to act on it. This module is a small process-orchestrator-style
+// supervisor, in the spirit of `mz_orchestrator_process`'s continuous
+// "ensure desired state" loop: on a timer, it re-queries live services,
+// compares `start_mode` against the recorded `end_mode`, and re-applies the
+// desired mode wherever they've drifted -- turning a saved target file into
+// an actively enforced desired state instead of a one-shot manual apply.
+
+use crate::{build_apply_command, get_services_from_system, read_targets_from_file, targets_file_path};
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default interval between reconciliation passes.
+pub const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+/// Runtime-adjustable state of the reconciliation loop, shared between the
+/// background task and the `GET /api/reconcile/status` / `POST
+/// /api/reconcile/{enable,disable}` handlers.
+struct ReconcileState {
+    enabled: bool,
+    /// When `true`, drift is corrected by re-applying `end_mode`. When
+    /// `false`, drift is only logged and counted (report-only).
+    enforce: bool,
+    interval_secs: u64,
+    last_run_unix_secs: Option<u64>,
+    /// Number of services found drifted (live `start_mode` != target
+    /// `end_mode`) during the most recent pass.
+    drift_count: u64,
+    /// Total number of corrections successfully applied since the process
+    /// started.
+    corrections_made: u64,
+    last_error: Option<String>,
+}
+
+impl Default for ReconcileState {
+    fn default() -> Self {
+        ReconcileState {
+            enabled: true,
+            enforce: true,
+            interval_secs: DEFAULT_INTERVAL_SECS,
+            last_run_unix_secs: None,
+            drift_count: 0,
+            corrections_made: 0,
+            last_error: None,
+        }
+    }
+}
+
+fn state() -> &'static Mutex<ReconcileState> {
+    static STATE: OnceLock<Mutex<ReconcileState>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(ReconcileState::default()))
+}
+
+/// Response body for `GET /api/reconcile/status`.
+#[derive(Debug, Serialize)]
+pub struct ReconcileStatusResponse {
+    pub enabled: bool,
+    pub enforce: bool,
+    pub interval_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_unix_secs: Option<u64>,
+    pub drift_count: u64,
+    pub corrections_made: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Returns a snapshot of the current reconciliation state.
+pub fn status() -> ReconcileStatusResponse {
+    let s = state().lock().unwrap();
+    ReconcileStatusResponse {
+        enabled: s.enabled,
+        enforce: s.enforce,
+        interval_secs: s.interval_secs,
+        last_run_unix_secs: s.last_run_unix_secs,
+        drift_count: s.drift_count,
+        corrections_made: s.corrections_made,
+        last_error: s.last_error.clone(),
+    }
+}
+
+/// Toggles the loop on/off and, where given, updates the interval and
+/// enforce/report-only behavior. `None` leaves the corresponding setting
+/// unchanged.
+pub fn configure(enabled: bool, interval_secs: Option<u64>, enforce: Option<bool>) {
+    let mut s = state().lock().unwrap();
+    s.enabled = enabled;
+    if let Some(secs) = interval_secs {
+        s.interval_secs = secs.max(1);
+    }
+    if let Some(enforce) = enforce {
+        s.enforce = enforce;
+    }
+}
+
+/// Runs the reconciliation loop until the process exits.
+///
+/// Sleeps for the configured interval between passes, re-checking `enabled`
+/// on every wake so `POST /api/reconcile/disable` takes effect within one
+/// interval without restarting the task.
+pub async fn run_loop() {
+    loop {
+        let interval = state().lock().unwrap().interval_secs;
+        actix_web::rt::time::sleep(Duration::from_secs(interval.max(1))).await;
+
+        if !state().lock().unwrap().enabled {
+            continue;
+        }
+
+        run_pass().await;
+    }
+}
+
+/// Runs one reconciliation pass and records its outcome in the shared state.
+async fn run_pass() {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let result = reconcile_once().await;
+
+    let mut s = state().lock().unwrap();
+    s.last_run_unix_secs = Some(now);
+    match result {
+        Ok((drift_count, corrections_made)) => {
+            s.drift_count = drift_count;
+            s.corrections_made += corrections_made;
+            s.last_error = None;
+        }
+        Err(e) => s.last_error = Some(e),
+    }
+}
+
+/// Loads the saved targets, re-queries live services per host they reference,
+/// and -- when `enforce` is set -- re-applies the recorded `end_mode`
+/// wherever it differs from the live `start_mode`.
+///
+/// # Returns
+/// `(drift_count, corrections_made)` for this pass.
+async fn reconcile_once() -> Result<(u64, u64), String> {
+    let Some(path) = targets_file_path() else {
+        return Err("Could not determine targets file path".to_string());
+    };
+    let Some(targets) = read_targets_from_file(&path) else {
+        return Ok((0, 0));
+    };
+
+    let enforce = state().lock().unwrap().enforce;
+
+    // Query each distinct host (including the local machine, `None`) once per pass.
+    let mut hosts_seen: Vec<Option<String>> = Vec::new();
+    for target in &targets {
+        if !hosts_seen.contains(&target.host) {
+            hosts_seen.push(target.host.clone());
+        }
+    }
+
+    let mut drift_count = 0u64;
+    let mut corrections_made = 0u64;
+
+    for host in hosts_seen {
+        let live = match get_services_from_system(host.as_deref()).await {
+            Ok(list) => list,
+            Err(e) => {
+                log::warn!("reconcile: failed to query host {:?}: {}", host, e);
+                continue;
+            }
+        };
+
+        for target in targets.iter().filter(|t| t.host == host) {
+            let Some(end_mode) = target.end_mode.as_ref() else { continue };
+            let Some(live_service) = live.iter().find(|s| s.name == target.name) else { continue };
+            if &live_service.start_mode == end_mode {
+                continue;
+            }
+
+            drift_count += 1;
+            log::info!(
+                "reconcile: drift on '{}' (host {:?}): live={} target={}",
+                target.name, host, live_service.start_mode, end_mode,
+            );
+
+            if !enforce {
+                continue;
+            }
+
+            match build_apply_command(&target.name, end_mode, host.as_deref()) {
+                Ok(command) => match command.run() {
+                    Ok(()) => {
+                        corrections_made += 1;
+                        log::info!("reconcile: corrected '{}' to {}", target.name, end_mode);
+                    }
+                    Err(e) => log::warn!("reconcile: failed to correct '{}': {}", target.name, e),
+                },
+                Err(e) => log::warn!("reconcile: cannot build apply command for '{}': {}", target.name, e),
+            }
+        }
+    }
+
+    Ok((drift_count, corrections_made))
+}
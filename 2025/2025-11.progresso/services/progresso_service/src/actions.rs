@@ -0,0 +1,110 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Action Plugin Registry
+//
+// Lifecycle handling was a fixed start/stop dichotomy baked into `engine`/`orchestrator`.
+// `ActionRegistry` turns it into an open set of named operations -- mirroring the IML
+// agent's `create_registry()`/`add_plugin(name, fn)` design and golem's typed
+// RPC-interface idea -- so new lifecycle operations (provisioning a service into the
+// SCM, tearing one down, or anything project-specific) can be added without touching
+// `ServiceEntry` or the core engine types.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::service_ctrl::run_sc;
+use crate::ServiceEntry;
+
+/// A single named lifecycle operation over a `ServiceEntry`.
+type Action = Box<dyn Fn(&mut ServiceEntry) -> Result<()> + Send + Sync>;
+
+/// A registry of named, pluggable lifecycle operations over `ServiceEntry`.
+///
+/// Callers register operations by name (`"start"`, `"stop"`, `"create"`, `"destroy"`, or
+/// any custom name) and dispatch by name, so the set of lifecycle operations stays
+/// open-ended rather than hardcoded into `engine`/`orchestrator`.
+#[derive(Default)]
+pub struct ActionRegistry {
+    plugins: HashMap<String, Action>,
+}
+
+impl ActionRegistry {
+    /// Creates an empty registry with no plugins registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `plugin` under `name`, replacing any plugin previously registered
+    /// under that name.
+    pub fn add_plugin<F>(&mut self, name: &str, plugin: F)
+    where
+        F: Fn(&mut ServiceEntry) -> Result<()> + Send + Sync + 'static,
+    {
+        self.plugins.insert(name.to_string(), Box::new(plugin));
+    }
+
+    /// Runs the plugin registered under `name` against `entry`.
+    ///
+    /// Returns an error if no plugin is registered under that name.
+    pub fn dispatch(&self, name: &str, entry: &mut ServiceEntry) -> Result<()> {
+        let plugin = self
+            .plugins
+            .get(name)
+            .ok_or_else(|| anyhow!("no action plugin registered for {name:?}"))?;
+        plugin(entry)
+    }
+
+    /// Returns `true` if a plugin is registered under `name`.
+    pub fn has_plugin(&self, name: &str) -> bool {
+        self.plugins.contains_key(name)
+    }
+}
+
+/// Derives the action name to dispatch for `entry` from its `end_mode`: `"start"` if
+/// [`ServiceEntry::should_start`], otherwise `"stop"`.
+pub fn action_for(entry: &ServiceEntry) -> &'static str {
+    if entry.should_start() {
+        "start"
+    } else {
+        "stop"
+    }
+}
+
+/// Builds the default registry: `"start"`/`"stop"` record their matching timestamp, and
+/// `"create"`/`"destroy"` provision/remove the service from the Windows SCM via
+/// `sc.exe`, mirroring IML's `ha_resource_create`/`ha_resource_destroy`.
+pub fn create_registry() -> ActionRegistry {
+    let mut registry = ActionRegistry::new();
+
+    registry.add_plugin("start", |entry| {
+        entry.record_start_processing();
+        entry.record_end();
+        Ok(())
+    });
+
+    registry.add_plugin("stop", |entry| {
+        entry.record_stop();
+        Ok(())
+    });
+
+    registry.add_plugin("create", |entry| {
+        let name = entry.name().ok_or_else(|| anyhow!("service entry has no name"))?;
+        let path = entry
+            .path
+            .as_deref()
+            .ok_or_else(|| anyhow!("service {name:?} has no path to provision"))?;
+        run_sc(&["create", name, "binPath=", path])?;
+        entry.record_start_processing();
+        Ok(())
+    });
+
+    registry.add_plugin("destroy", |entry| {
+        let name = entry.name().ok_or_else(|| anyhow!("service entry has no name"))?;
+        run_sc(&["delete", name])?;
+        entry.record_stop();
+        Ok(())
+    });
+
+    registry
+}
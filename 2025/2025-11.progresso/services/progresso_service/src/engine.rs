@@ -0,0 +1,218 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Dependency-Ordered Orchestration Engine
+//
+// `OrdemTargets` is literally an "ordem" (ordering) of services, yet historically the
+// harness just processed entries in vector order with no real sequencing. This engine
+// topologically sorts each entry's `depends_on` edges into layers and starts
+// independent subgraphs concurrently on worker threads, starting a node only after all
+// of its dependencies have reached RUNNING.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::service_ctrl::{wait_for_state_with, ServiceController};
+use crate::{OrdemTargets, ServiceEntry};
+
+/// Outcome of processing a single service through the engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceOutcome {
+    /// Reached its desired end state within the timeout.
+    Started,
+    /// A start/stop command was issued but the state transition timed out.
+    TimedOut,
+    /// Never attempted because a dependency failed or timed out.
+    SkippedDependencyFailed,
+}
+
+/// Aggregate result of an orchestration run: one outcome per service name.
+#[derive(Debug, Default, Clone)]
+pub struct EngineReport {
+    pub outcomes: HashMap<String, ServiceOutcome>,
+}
+
+/// A `depends_on` cycle was detected among the named services.
+#[derive(Debug, Clone)]
+pub struct CycleError {
+    pub services: Vec<String>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "dependency cycle detected among services: {}", self.services.join(", "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// Splits `entries` into topologically-ordered layers: each inner `Vec` names services
+/// whose dependencies are all satisfied by earlier layers, so they can run
+/// concurrently. Unknown dependency names (referencing an entry not present in
+/// `entries`) are ignored rather than treated as unsatisfiable.
+///
+/// Returns [`CycleError`] listing the services still unresolved once no further
+/// progress can be made.
+///
+/// `pub` (rather than `pub(crate)`) so callers outside the library, such as the
+/// integration tests, can inspect the layers directly. `main.rs`'s `run_main` reaches
+/// it through [`OrdemTargets::processing_order`] to reorder `OrdemTargets::services`
+/// into dependency order, without duplicating the topological sort.
+pub fn layered_order(entries: &[ServiceEntry]) -> Result<Vec<Vec<String>>, CycleError> {
+    let mut remaining: HashMap<String, HashSet<String>> = HashMap::new();
+    for entry in entries {
+        let Some(name) = entry.name() else { continue };
+        remaining.insert(name.to_string(), entry.depends_on.iter().cloned().collect());
+    }
+
+    let known: HashSet<String> = remaining.keys().cloned().collect();
+    for deps in remaining.values_mut() {
+        deps.retain(|d| known.contains(d));
+    }
+
+    let mut layers = Vec::new();
+    while !remaining.is_empty() {
+        let mut ready: Vec<String> = remaining
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        if ready.is_empty() {
+            let mut stuck: Vec<String> = remaining.keys().cloned().collect();
+            stuck.sort();
+            return Err(CycleError { services: stuck });
+        }
+        ready.sort();
+
+        for name in &ready {
+            remaining.remove(name);
+        }
+        for deps in remaining.values_mut() {
+            for name in &ready {
+                deps.remove(name);
+            }
+        }
+
+        layers.push(ready);
+    }
+
+    Ok(layers)
+}
+
+/// Runs `ordem`'s services in dependency order, starting each topological layer's
+/// services concurrently on worker threads.
+///
+/// A service is skipped with [`ServiceOutcome::SkippedDependencyFailed`] if any of its
+/// `depends_on` entries timed out or were themselves skipped. `stop_flag` aborts the
+/// whole run (already-running layers finish, but no further layer starts). Returns an
+/// aggregate [`EngineReport`], or [`CycleError`] if `depends_on` edges form a cycle.
+pub fn run(
+    controller: &(dyn ServiceController),
+    ordem: &mut OrdemTargets,
+    stop_flag: Arc<AtomicBool>,
+    timeout_secs: u64,
+) -> Result<EngineReport, CycleError> {
+    let layers = layered_order(&ordem.services)?;
+
+    // Wrap each entry so independent worker threads can mutate their own entry (to
+    // record timestamps) without requiring disjoint slice borrows across threads.
+    let mut by_name: HashMap<String, Arc<Mutex<ServiceEntry>>> = HashMap::new();
+    let mut order: Vec<String> = Vec::with_capacity(ordem.services.len());
+    for entry in ordem.services.drain(..) {
+        if let Some(name) = entry.name().map(str::to_string) {
+            order.push(name.clone());
+            by_name.insert(name, Arc::new(Mutex::new(entry)));
+        }
+    }
+
+    let report: Mutex<EngineReport> = Mutex::new(EngineReport::default());
+    let failed: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+
+    for layer in &layers {
+        if stop_flag.load(Ordering::SeqCst) {
+            break;
+        }
+
+        thread::scope(|scope| {
+            for name in layer {
+                let Some(entry_lock) = by_name.get(name) else { continue };
+
+                let any_dep_failed = {
+                    let failed = failed.lock().unwrap();
+                    let entry = entry_lock.lock().unwrap();
+                    entry.depends_on.iter().any(|d| failed.contains(d))
+                };
+
+                if any_dep_failed {
+                    failed.lock().unwrap().insert(name.clone());
+                    report
+                        .lock()
+                        .unwrap()
+                        .outcomes
+                        .insert(name.clone(), ServiceOutcome::SkippedDependencyFailed);
+                    continue;
+                }
+
+                let stop_flag = Arc::clone(&stop_flag);
+                scope.spawn(move || {
+                    let outcome = process_one(controller, entry_lock, name, &stop_flag, timeout_secs);
+                    if outcome != ServiceOutcome::Started {
+                        failed.lock().unwrap().insert(name.clone());
+                    }
+                    report.lock().unwrap().outcomes.insert(name.clone(), outcome);
+                });
+            }
+        });
+    }
+
+    // Restore original order.
+    ordem.services = order
+        .into_iter()
+        .filter_map(|name| by_name.remove(&name))
+        .map(|cell| Arc::try_unwrap(cell).expect("no outstanding references after scope").into_inner().unwrap())
+        .collect();
+
+    Ok(report.into_inner().unwrap())
+}
+
+/// Starts or stops a single service (per `should_start`), waits for the transition,
+/// and records the relevant timestamps on its `ServiceEntry`.
+fn process_one(
+    controller: &dyn ServiceController,
+    entry_lock: &Arc<Mutex<ServiceEntry>>,
+    name: &str,
+    stop_flag: &Arc<AtomicBool>,
+    timeout_secs: u64,
+) -> ServiceOutcome {
+    let should_start = {
+        let mut entry = entry_lock.lock().unwrap();
+        entry.record_start_processing();
+        entry.should_start()
+    };
+
+    let desired = if should_start {
+        let _ = controller.start(name);
+        "RUNNING"
+    } else {
+        let _ = controller.stop(name);
+        "STOPPED"
+    };
+
+    let reached = wait_for_state_with(controller, name, desired, timeout_secs, Arc::clone(stop_flag));
+
+    let mut entry = entry_lock.lock().unwrap();
+    if should_start {
+        entry.record_end();
+    } else {
+        entry.record_stop();
+    }
+
+    if reached {
+        ServiceOutcome::Started
+    } else {
+        ServiceOutcome::TimedOut
+    }
+}
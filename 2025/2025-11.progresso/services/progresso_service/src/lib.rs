@@ -18,14 +18,97 @@
 // - parse_ordem: Deserialize XML configuration into Rust structures
 // - write_progress_xml: Serialize progress data back to XML
 
-use chrono::Local;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use serde_xml_rs::from_str;
 use anyhow::Result;
 use quick_xml::se::to_string as to_xml_string;
 
 // Re-export service_ctrl module for external use
+pub mod actions;
+pub mod engine;
+pub mod orchestrator;
+pub mod run_key;
 pub mod service_ctrl;
+pub mod service_log;
+pub mod supervisor;
+pub mod timestamp;
+pub mod timing;
+
+pub use timestamp::Timestamp;
+
+/// Default restart delay when `restart_delay_secs` is not set in the XML.
+const DEFAULT_RESTART_DELAY_SECS: u64 = 5;
+
+/// Default restart cap when `max_restarts` is not set in the XML.
+const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// Default CPU usage threshold (percent), below which `progresso_service`'s `main.rs`
+/// considers the system settled, when `cpu_threshold` is not set in the XML.
+const DEFAULT_CPU_THRESHOLD: f32 = 60.0;
+
+/// Default interval, in seconds, between CPU usage polls when `cpu_poll_interval_secs`
+/// is not set in the XML.
+const DEFAULT_CPU_POLL_INTERVAL_SECS: u64 = 1;
+
+/// Default maximum time, in seconds, to wait for CPU to drop below threshold when
+/// `cpu_wait_timeout_secs` is not set in the XML.
+const DEFAULT_CPU_WAIT_TIMEOUT_SECS: u64 = 300;
+
+/// Supervision policy applied after a service reaches RUNNING, mirroring how a
+/// service-wrapper tool lets you declare relaunch behavior.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// Never restart the service if it stops.
+    #[default]
+    #[serde(rename = "never")]
+    Never,
+    /// Restart the service only if it stops unexpectedly (not via a requested stop).
+    #[serde(rename = "on-failure")]
+    OnFailure,
+    /// Always restart the service whenever it stops.
+    #[serde(rename = "always")]
+    Always,
+}
+
+/// Action to take when a service fails to reach its target start/stop state
+/// (after its backoff/retry loop is exhausted), mirroring NSSM's `AppExit`
+/// behavior. Interpreted by `progresso_service`'s `main.rs` run loop.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExitAction {
+    /// Re-attempt the start/stop via the backoff loop one more time.
+    #[serde(rename = "restart")]
+    Restart,
+    /// Log the failure and continue to the next service.
+    #[default]
+    #[serde(rename = "ignore")]
+    Ignore,
+    /// Abort the whole run: the run loop returns `Err`, exiting nonzero.
+    #[serde(rename = "fail")]
+    Fail,
+    /// Stop processing immediately, as if a shutdown had been requested.
+    #[serde(rename = "abort")]
+    Abort,
+}
+
+/// Relative CPU scheduling priority for a supervised process, mirroring the levels
+/// exposed by the Windows Services MMC snap-in.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessPriority {
+    #[serde(rename = "realtime")]
+    Realtime,
+    #[serde(rename = "high")]
+    High,
+    #[serde(rename = "above-normal")]
+    AboveNormal,
+    #[serde(rename = "normal")]
+    Normal,
+    #[serde(rename = "below-normal")]
+    BelowNormal,
+    #[serde(rename = "idle")]
+    Idle,
+}
 
 /// Root structure containing all target service configurations.
 ///
@@ -77,6 +160,26 @@ impl OrdemTargets {
     pub fn is_empty(&self) -> bool {
         self.services.is_empty()
     }
+
+    /// Returns service names in dependency-respecting start order: a Kahn topological
+    /// sort of each entry's `depends_on` edges, so a service never precedes one it
+    /// depends on. Services are emitted one layer of [`engine::layered_order`] at a time,
+    /// and services within a layer are ordered by name, not by their position in
+    /// [`Self::services`].
+    ///
+    /// Returns [`engine::CycleError`] if `depends_on` edges form a cycle.
+    pub fn processing_order(&self) -> std::result::Result<Vec<String>, engine::CycleError> {
+        let layers = engine::layered_order(&self.services)?;
+        Ok(layers.into_iter().flatten().collect())
+    }
+
+    /// Returns service names in stop order: the reverse of [`Self::processing_order`],
+    /// so a service is stopped only after everything that depends on it.
+    pub fn stop_order(&self) -> std::result::Result<Vec<String>, engine::CycleError> {
+        let mut order = self.processing_order()?;
+        order.reverse();
+        Ok(order)
+    }
 }
 
 /// Represents a single Windows service with its configuration and execution timestamps.
@@ -102,35 +205,118 @@ impl OrdemTargets {
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct ServiceEntry {
     /// Service name identifier (matches Windows service name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     /// Human-readable service description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     /// Current service status at configuration time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
     /// Service startup mode (e.g., "Manual", "Automatic", "Disabled").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub start_mode: Option<String>,
     /// Target end state: "Automatic" means start the service, other values mean stop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub end_mode: Option<String>,
     /// Account under which the service runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub log_on_as: Option<String>,
     /// Path to the service executable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
 
-    /// Timestamp (RFC 3339) when processing of this service began.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub start_processing_time: Option<String>,
+    /// Activation backend for this entry: `"service"` (default, managed by the
+    /// Windows SCM via `sc`) or `"run-key"` (registered under
+    /// `HKCU\...\Run` and launched directly; see [`crate::run_key`]). Absent means
+    /// `"service"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+
+    /// Names of other entries (by `name`) that must reach RUNNING before this one
+    /// starts. "Ordem" ("ordering") implies this: see [`crate::engine`] for how these
+    /// edges are resolved into a dependency-ordered, concurrent run.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+
+    /// Timestamp when processing of this service began.
+    #[serde(default, with = "timestamp::serde_rfc3339", skip_serializing_if = "Option::is_none")]
+    pub start_processing_time: Option<Timestamp>,
 
-    /// Timestamp (RFC 3339) when a stop command was issued.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop_time: Option<String>,
+    /// Timestamp when a stop command was issued.
+    #[serde(default, with = "timestamp::serde_rfc3339", skip_serializing_if = "Option::is_none")]
+    pub stop_time: Option<Timestamp>,
 
-    /// Timestamp (RFC 3339) when processing of this service completed.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub end_time: Option<String>,
+    /// Timestamp when processing of this service completed.
+    #[serde(default, with = "timestamp::serde_rfc3339", skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<Timestamp>,
 
-    /// Timestamp (RFC 3339) when CPU usage dropped below threshold.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpu_responsive_time: Option<String>,
+    /// Timestamp when CPU usage dropped below threshold.
+    #[serde(default, with = "timestamp::serde_rfc3339", skip_serializing_if = "Option::is_none")]
+    pub cpu_responsive_time: Option<Timestamp>,
+
+    /// Milliseconds from run start until processing of this service began. See
+    /// [`crate::timing`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_processing_duration_ms: Option<u64>,
+
+    /// Milliseconds from run start until a stop command was issued. See
+    /// [`crate::timing`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_duration_ms: Option<u64>,
+
+    /// Milliseconds from run start until CPU usage dropped below threshold. See
+    /// [`crate::timing`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_responsive_duration_ms: Option<u64>,
+
+    /// Restart policy to enforce once this service reaches RUNNING. Defaults to
+    /// [`RestartPolicy::Never`] when absent, preserving today's start-once behavior.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<RestartPolicy>,
+
+    /// Delay, in seconds, before re-issuing a start after an unexpected stop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_delay_secs: Option<u64>,
+
+    /// Maximum restart attempts within the supervisor's sliding window before the
+    /// entry is marked failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_restarts: Option<u32>,
+
+    /// Action to take when processing reaches this service's end: e.g. how it should
+    /// be stopped. Free-form; it is parsed and serialized, but nothing in this crate
+    /// reads it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_action: Option<String>,
+
+    /// CPU scheduling priority declared for the service process. It is parsed and
+    /// serialized only; nothing in this crate applies it to a process.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<ProcessPriority>,
+
+    /// Action to take if this service fails to reach its target start/stop
+    /// state. Defaults to [`ExitAction::Ignore`], preserving today's
+    /// best-effort behavior.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_action: Option<ExitAction>,
+
+    /// CPU usage threshold (percent) below which the post-processing CPU
+    /// stabilization wait considers the system settled. Overrides
+    /// [`DEFAULT_CPU_THRESHOLD`]; some services legitimately spike CPU and
+    /// shouldn't block the ordered run on a one-size-fits-all gate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_threshold: Option<f32>,
+
+    /// Interval, in seconds, between CPU usage polls during the stabilization wait.
+    /// Overrides [`DEFAULT_CPU_POLL_INTERVAL_SECS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_poll_interval_secs: Option<u64>,
+
+    /// Maximum time, in seconds, to wait for CPU to drop below `cpu_threshold`
+    /// before giving up. Overrides [`DEFAULT_CPU_WAIT_TIMEOUT_SECS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_wait_timeout_secs: Option<u64>,
 }
 
 impl ServiceEntry {
@@ -154,25 +340,81 @@ impl ServiceEntry {
     /// Records the current time as the start processing timestamp.
     #[inline]
     pub fn record_start_processing(&mut self) {
-        self.start_processing_time = Some(Local::now().to_rfc3339());
+        self.start_processing_time = Some(timestamp::now());
     }
 
     /// Records the current time as the stop timestamp.
     #[inline]
     pub fn record_stop(&mut self) {
-        self.stop_time = Some(Local::now().to_rfc3339());
+        self.stop_time = Some(timestamp::now());
     }
 
     /// Records the current time as the end timestamp.
     #[inline]
     pub fn record_end(&mut self) {
-        self.end_time = Some(Local::now().to_rfc3339());
+        self.end_time = Some(timestamp::now());
     }
 
     /// Records the current time as the CPU responsive timestamp.
     #[inline]
     pub fn record_cpu_responsive(&mut self) {
-        self.cpu_responsive_time = Some(Local::now().to_rfc3339());
+        self.cpu_responsive_time = Some(timestamp::now());
+    }
+
+    /// Returns the effective restart policy, defaulting to [`RestartPolicy::Never`].
+    #[inline]
+    pub fn restart_policy(&self) -> RestartPolicy {
+        self.restart.unwrap_or_default()
+    }
+
+    /// Returns the effective restart delay, defaulting to
+    /// [`DEFAULT_RESTART_DELAY_SECS`].
+    #[inline]
+    pub fn restart_delay(&self) -> Duration {
+        Duration::from_secs(self.restart_delay_secs.unwrap_or(DEFAULT_RESTART_DELAY_SECS))
+    }
+
+    /// Returns the effective restart budget, defaulting to [`DEFAULT_MAX_RESTARTS`].
+    #[inline]
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS)
+    }
+
+    /// Returns the effective exit action, defaulting to [`ExitAction::Ignore`].
+    #[inline]
+    pub fn exit_action(&self) -> ExitAction {
+        self.exit_action.unwrap_or_default()
+    }
+
+    /// Returns the effective CPU threshold (percent), defaulting to
+    /// [`DEFAULT_CPU_THRESHOLD`].
+    #[inline]
+    pub fn cpu_threshold(&self) -> f32 {
+        self.cpu_threshold.unwrap_or(DEFAULT_CPU_THRESHOLD)
+    }
+
+    /// Returns the effective CPU poll interval, defaulting to
+    /// [`DEFAULT_CPU_POLL_INTERVAL_SECS`].
+    #[inline]
+    pub fn cpu_poll_interval(&self) -> Duration {
+        Duration::from_secs(self.cpu_poll_interval_secs.unwrap_or(DEFAULT_CPU_POLL_INTERVAL_SECS))
+    }
+
+    /// Returns the effective CPU stabilization wait timeout, defaulting to
+    /// [`DEFAULT_CPU_WAIT_TIMEOUT_SECS`].
+    #[inline]
+    pub fn cpu_wait_timeout(&self) -> Duration {
+        Duration::from_secs(self.cpu_wait_timeout_secs.unwrap_or(DEFAULT_CPU_WAIT_TIMEOUT_SECS))
+    }
+
+    /// Returns `true` if this entry should be activated via the Run-key backend (see
+    /// [`crate::run_key`]) instead of the default SCM-managed `sc` backend.
+    #[inline]
+    pub fn uses_run_key_backend(&self) -> bool {
+        self.backend
+            .as_deref()
+            .map(|b| b.eq_ignore_ascii_case("run-key"))
+            .unwrap_or(false)
     }
 }
 
@@ -227,6 +469,46 @@ pub fn write_progress_xml(progress: &OrdemTargets) -> Result<String> {
     Ok(to_xml_string(progress)?)
 }
 
+/// Serializes progress data to JSON format.
+///
+/// Reuses the same `serde` derives as the XML path, so the `skip_serializing_if =
+/// "Option::is_none"` behavior is honored and unset timestamp/duration fields are
+/// omitted rather than emitted as `null`. Gated behind the `json` Cargo feature to
+/// keep the default build lean; the on-disk XML format is unaffected either way.
+///
+/// # Arguments
+///
+/// * `progress` - The progress data to serialize
+///
+/// # Returns
+///
+/// * `Ok(String)` - JSON string
+/// * `Err` - Serialization failed
+#[cfg(feature = "json")]
+#[inline]
+pub fn write_progress_json(progress: &OrdemTargets) -> Result<String> {
+    Ok(serde_json::to_string(progress)?)
+}
+
+/// Parses ordem target configuration from a JSON string.
+///
+/// Mirrors [`parse_ordem`], but for the JSON representation produced by
+/// [`write_progress_json`]. Gated behind the `json` Cargo feature.
+///
+/// # Arguments
+///
+/// * `json` - JSON string containing service configurations in `OrdemTargets` format
+///
+/// # Returns
+///
+/// * `Ok(OrdemTargets)` - Successfully parsed configuration
+/// * `Err(serde_json::Error)` - JSON parsing or deserialization failed
+#[cfg(feature = "json")]
+#[inline]
+pub fn parse_ordem_json(json: &str) -> Result<OrdemTargets, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
 /// Populates all empty timestamp fields with the current time.
 ///
 /// This is primarily useful for testing scenarios where all timestamps need
@@ -250,12 +532,12 @@ pub fn write_progress_xml(progress: &OrdemTargets) -> Result<String> {
 /// assert!(progress.services[0].start_processing_time.is_some());
 /// ```
 pub fn populate_test_timestamps(progress: &mut OrdemTargets) {
-    let now = Local::now().to_rfc3339();
+    let now = timestamp::now();
     for service in &mut progress.services {
-        service.start_processing_time.get_or_insert_with(|| now.clone());
-        service.stop_time.get_or_insert_with(|| now.clone());
-        service.end_time.get_or_insert_with(|| now.clone());
-        service.cpu_responsive_time.get_or_insert_with(|| now.clone());
+        service.start_processing_time.get_or_insert(now);
+        service.stop_time.get_or_insert(now);
+        service.end_time.get_or_insert(now);
+        service.cpu_responsive_time.get_or_insert(now);
     }
 }
 
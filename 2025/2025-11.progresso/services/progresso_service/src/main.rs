@@ -13,12 +13,13 @@
 // # Operation
 // 1. Reads target configurations
 // 2. For each service, starts/stops based on end_mode
-// 3. Waits for CPU usage to drop below threshold (60%)
+// 3. Waits for CPU usage to drop below threshold (60% by default, per-service tunable)
 // 4. Records timestamps for each operation
 // 5. Writes incremental progress to XML file
 
 use chrono::Local;
 use serde_xml_rs::from_str;
+use std::collections::HashMap;
 use std::fs;
 use std::io::{BufWriter, Write};
 use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
@@ -27,11 +28,13 @@ use std::time::{Duration, Instant};
 use sysinfo::System;
 
 use anyhow::Result;
-use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult, ServiceStatusHandle};
 use windows_service::service_dispatcher;
 use windows_service::service::{ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType};
 
-use progresso_service::{OrdemTargets, service_ctrl};
+use progresso_service::engine::{EngineReport, ServiceOutcome};
+use progresso_service::timing::{self, PhaseTimer};
+use progresso_service::{ExitAction, OrdemTargets, service_ctrl};
 
 /// Windows service name as registered with the Service Control Manager.
 const SERVICE_NAME: &str = "ProgressoService";
@@ -43,16 +46,28 @@ const INPUT_FILE: &str = "ordem.target.xml";
 const OUTPUT_PREFIX: &str = "progresso";
 
 // Performance tuning constants
-/// Interval between CPU usage polls.
-const CPU_POLL_INTERVAL: Duration = Duration::from_secs(1);
-/// Maximum time to wait for CPU to drop below threshold (5 minutes).
-const CPU_WAIT_TIMEOUT: Duration = Duration::from_secs(300);
+//
+// The CPU threshold/poll interval/wait timeout are no longer fixed here: each
+// `ServiceEntry` supplies its effective values (defaulting to
+// `progresso_service::lib.rs`'s `DEFAULT_CPU_*` constants) via
+// `cpu_threshold()`/`cpu_poll_interval()`/`cpu_wait_timeout()`, read by
+// `wait_for_cpu_stable`.
 /// Maximum time to wait for a service state transition (1 minute).
 const SERVICE_STATE_TIMEOUT: Duration = Duration::from_secs(60);
-/// CPU usage threshold percentage - processing continues when below this value.
-const CPU_THRESHOLD: f32 = 60.0;
 /// Minimum CPU change percentage to report (reduces log spam).
 const CPU_REPORT_DELTA: f32 = 5.0;
+/// Maximum number of attempts to start/stop a service before giving up.
+const MAX_RETRIES: u32 = 3;
+/// Granularity of the backoff sleep, so a pending `stop_flag` is noticed
+/// promptly instead of only after the full delay.
+const BACKOFF_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Granularity of the poll while suspended for a `Pause` control, so a
+/// `Continue` or `Stop` is noticed promptly.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Margin added on top of the next poll interval when computing the `wait_hint`
+/// for a pending-state checkpoint, so the SCM's own deadline for the *next*
+/// checkpoint comfortably outlives it.
+const SCM_WAIT_HINT_MARGIN: Duration = Duration::from_secs(5);
 
 /// Application entry point.
 ///
@@ -66,28 +81,97 @@ fn main() {
         println!("Warning: Not running as service ({}), falling back to console mode", e);
 
         let stop_flag = Arc::new(AtomicBool::new(false));
-        if let Err(e) = run_main(stop_flag) {
+        let pause_flag = Arc::new(AtomicBool::new(false));
+        if let Err(e) = run_main(stop_flag, pause_flag, None) {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
     }
 }
 
+/// The set of controls this service accepts while running: `Stop`,
+/// `Preshutdown` (so the SCM grants extra time to flush the final
+/// `write_progress_file` during an OS shutdown instead of killing the process
+/// outright), and `Pause`/`Continue`.
+fn accepted_controls() -> ServiceControlAccept {
+    ServiceControlAccept::STOP | ServiceControlAccept::PRESHUTDOWN | ServiceControlAccept::PAUSE_CONTINUE
+}
+
+/// Pushes `state` to the SCM via `status_handle`, advertising
+/// [`accepted_controls`]. A no-op in console mode, where `status_handle` is `None`.
+fn report_status(status_handle: Option<ServiceStatusHandle>, state: ServiceState) {
+    let Some(handle) = status_handle else { return };
+    let _ = handle.set_service_status(ServiceStatus {
+        service_type: ServiceType::OWN_PROCESS,
+        current_state: state,
+        controls_accepted: accepted_controls(),
+        exit_code: ServiceExitCode::Win32(0),
+        checkpoint: 0,
+        wait_hint: Duration::from_secs(10),
+        process_id: None,
+    });
+}
+
+/// Pushes a pending-state checkpoint to the SCM during a long-running wait
+/// (CPU stabilization or a service state transition), so the SCM's own hung-service
+/// detection doesn't fire while we're still legitimately making progress.
+///
+/// `state` is [`ServiceState::StartPending`] while processing is still underway, or
+/// [`ServiceState::StopPending`] once a shutdown has been requested (`stop_flag` is
+/// set). `checkpoint` must increase on every call; `wait_hint` should comfortably
+/// outlive the caller's poll interval. A no-op in console mode.
+fn report_pending(
+    status_handle: Option<ServiceStatusHandle>,
+    stop_flag: &Arc<AtomicBool>,
+    checkpoint: u32,
+    wait_hint: Duration,
+) {
+    let Some(handle) = status_handle else { return };
+    let state = if stop_flag.load(Ordering::SeqCst) {
+        ServiceState::StopPending
+    } else {
+        ServiceState::StartPending
+    };
+    let _ = handle.set_service_status(ServiceStatus {
+        service_type: ServiceType::OWN_PROCESS,
+        current_state: state,
+        controls_accepted: ServiceControlAccept::empty(),
+        exit_code: ServiceExitCode::Win32(0),
+        checkpoint,
+        wait_hint,
+        process_id: None,
+    });
+}
+
 /// Windows service entry point called by the Service Control Manager.
 ///
-/// Registers a control handler for stop/interrogate commands, signals the service
-/// as running, executes the main worker, then signals stopped on completion.
+/// Registers a control handler for stop/preshutdown/pause/continue/interrogate
+/// commands, signals the service as running, executes the main worker, then
+/// signals stopped on completion.
 extern "system" fn service_main(_argc: u32, _argv: *mut *mut u16) {
     let stop_flag = Arc::new(AtomicBool::new(false));
+    let pause_flag = Arc::new(AtomicBool::new(false));
     let flag_clone = Arc::clone(&stop_flag);
+    let pause_flag_clone = Arc::clone(&pause_flag);
 
-    // Register handler for service control events (stop, interrogate)
+    // Register handler for service control events
     let status_handle = match service_control_handler::register(SERVICE_NAME, move |event| {
         match event {
-            ServiceControl::Stop | ServiceControl::Interrogate => {
+            ServiceControl::Stop | ServiceControl::Preshutdown => {
                 flag_clone.store(true, Ordering::SeqCst);
                 ServiceControlHandlerResult::NoError
             }
+            // Interrogate is a status query, not a stop request; the SCM already has
+            // our last-reported status, so there's nothing to update here.
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            ServiceControl::Pause => {
+                pause_flag_clone.store(true, Ordering::SeqCst);
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Continue => {
+                pause_flag_clone.store(false, Ordering::SeqCst);
+                ServiceControlHandlerResult::NoError
+            }
             _ => ServiceControlHandlerResult::NotImplemented,
         }
     }) {
@@ -99,19 +183,10 @@ extern "system" fn service_main(_argc: u32, _argv: *mut *mut u16) {
     };
 
     // Notify SCM that the service is now running
-    let running_status = ServiceStatus {
-        service_type: ServiceType::OWN_PROCESS,
-        current_state: ServiceState::Running,
-        controls_accepted: ServiceControlAccept::STOP,
-        exit_code: ServiceExitCode::Win32(0),
-        checkpoint: 0,
-        wait_hint: Duration::from_secs(10),
-        process_id: None,
-    };
-    let _ = status_handle.set_service_status(running_status);
+    report_status(Some(status_handle), ServiceState::Running);
 
     // Execute main processing loop
-    if let Err(e) = run_main(Arc::clone(&stop_flag)) {
+    if let Err(e) = run_main(Arc::clone(&stop_flag), Arc::clone(&pause_flag), Some(status_handle)) {
         eprintln!("Service worker error: {}", e);
     }
 
@@ -130,28 +205,65 @@ extern "system" fn service_main(_argc: u32, _argv: *mut *mut u16) {
 
 /// Main worker function that processes all target services.
 ///
-/// Workflow for each service:
-/// 1. Records start processing timestamp
-/// 2. Checks current running state
-/// 3. Starts or stops service based on `end_mode` configuration
-/// 4. Waits for state transition to complete
-/// 5. Waits for CPU usage to drop below threshold
-/// 6. Records completion timestamps
-/// 7. Writes incremental progress to output file
+/// Workflow:
+/// 1. Sequences services via [`OrdemTargets::processing_order`], so a service never
+///    starts before its dependencies
+/// 2. For each service, in that order:
+///    1. Records start processing timestamp
+///    2. Checks current running state
+///    3. Starts or stops service based on `end_mode` configuration
+///    4. Waits for state transition to complete
+///    5. Waits for CPU usage to drop below threshold
+///    6. Records completion timestamps
+///    7. Writes incremental progress to output file
+/// 3. Prints an aggregate [`EngineReport`] summary once the run completes
 ///
 /// # Arguments
 ///
 /// * `stop_flag` - Atomic flag for graceful shutdown signaling. When set to `true`,
 ///   the function will complete the current service and exit the loop.
+/// * `pause_flag` - Atomic flag for a `Pause` control. When set to `true`, the
+///   function suspends between services (and while waiting for CPU to
+///   stabilize) until it's cleared by a `Continue` control or `stop_flag` is set.
+/// * `status_handle` - SCM status handle used to report `ServiceState::Paused`/
+///   `Running` transitions; `None` in console mode, where there's no SCM to report to.
 ///
 /// # Returns
 ///
 /// * `Ok(())` - All services processed (or stopped early via flag)
-/// * `Err` - File I/O or XML parsing failed
-fn run_main(stop_flag: Arc<AtomicBool>) -> Result<()> {
+/// * `Err` - File I/O or XML parsing failed, `depends_on` entries formed a cycle, or a
+///   service's `exit_action` was `Fail`
+fn run_main(
+    stop_flag: Arc<AtomicBool>,
+    pause_flag: Arc<AtomicBool>,
+    status_handle: Option<ServiceStatusHandle>,
+) -> Result<()> {
     // Read and parse input configuration
     let raw_xml = fs::read_to_string(INPUT_FILE)?;
-    let ordem: OrdemTargets = from_str(&raw_xml).unwrap_or_default();
+    let mut ordem: OrdemTargets = from_str(&raw_xml).unwrap_or_default();
+
+    // Sequence services by `depends_on` order (see `OrdemTargets::processing_order`,
+    // backed by `engine::layered_order`) instead of raw XML order, so a service never
+    // starts before its dependencies. The loop below still processes them one at a
+    // time (retaining its retry/CPU-wait/pause/SCM reporting behavior), just in
+    // dependency order rather than document order.
+    let order = ordem.processing_order().map_err(|cycle| anyhow::anyhow!("{}", cycle))?;
+    let mut by_name: HashMap<String, progresso_service::ServiceEntry> = HashMap::new();
+    let mut unnamed: Vec<progresso_service::ServiceEntry> = Vec::new();
+    for entry in ordem.services.drain(..) {
+        match entry.name().map(str::to_string) {
+            Some(name) => {
+                by_name.insert(name, entry);
+            }
+            None => unnamed.push(entry),
+        }
+    }
+    for name in &order {
+        if let Some(entry) = by_name.remove(name) {
+            ordem.services.push(entry);
+        }
+    }
+    ordem.services.extend(unnamed);
 
     // Create timestamped output file
     let timestamp = Local::now().format("%Y%m%d.%H%M%S");
@@ -161,6 +273,12 @@ fn run_main(stop_flag: Arc<AtomicBool>) -> Result<()> {
     // Pre-allocate progress tracking with known capacity
     let mut progress = OrdemTargets::with_capacity(ordem.len());
 
+    // Aggregate per-service outcome, reported once the run completes.
+    let mut report = EngineReport::default();
+
+    // Anchors per-phase elapsed-time stamping (see `timing::print_duration_summary`).
+    let phase_timer = PhaseTimer::start();
+
     // Initialize CPU monitoring system
     let mut sys = System::new_all();
 
@@ -173,7 +291,14 @@ fn run_main(stop_flag: Arc<AtomicBool>) -> Result<()> {
             break;
         }
 
+        wait_while_paused(&pause_flag, &stop_flag, status_handle);
+        if stop_flag.load(Ordering::SeqCst) {
+            eprintln!("Stop requested while paused");
+            break;
+        }
+
         svc.record_start_processing();
+        phase_timer.record_start_processing(&mut svc);
 
         // Extract service name - check early to avoid unnecessary work
         // Clone the name string to avoid borrow checker issues (we need mutable access to svc later)
@@ -191,18 +316,71 @@ fn run_main(stop_flag: Arc<AtomicBool>) -> Result<()> {
         // Capture initial state before any modifications
         let was_running = service_ctrl::is_service_running(&svc_name);
         svc.record_stop();
+        phase_timer.record_stop(&mut svc);
         svc.record_end();
 
         // Process based on end_mode configuration
         // Clone end_mode to avoid borrow checker complexity (it's a small string)
         if let Some(end_mode) = svc.end_mode.clone() {
-            process_service_action(&mut svc, &svc_name, &end_mode, was_running, &stop_flag);
+            let outcome = process_service_action(
+                &mut svc,
+                &svc_name,
+                &end_mode,
+                was_running,
+                &stop_flag,
+                status_handle,
+                &phase_timer,
+            );
+            let mut final_outcome = ServiceOutcome::Started;
+
+            if outcome == ProcessOutcome::Failed {
+                final_outcome = ServiceOutcome::TimedOut;
+                match svc.exit_action() {
+                    ExitAction::Restart => {
+                        log::warn!("Service '{}' failed; exit_action=Restart, retrying once.", svc_name);
+                        println!("  exit_action=Restart: retrying...");
+                        if process_service_action(
+                            &mut svc,
+                            &svc_name,
+                            &end_mode,
+                            was_running,
+                            &stop_flag,
+                            status_handle,
+                            &phase_timer,
+                        ) == ProcessOutcome::Failed
+                        {
+                            log::warn!("Service '{}' still failed after the Restart retry.", svc_name);
+                        } else {
+                            final_outcome = ServiceOutcome::Started;
+                        }
+                    }
+                    ExitAction::Ignore => {
+                        log::warn!("Service '{}' failed; exit_action=Ignore, continuing.", svc_name);
+                    }
+                    ExitAction::Fail => {
+                        progress.services.push(svc);
+                        write_progress_file(&progress, &output_path)?;
+                        anyhow::bail!("Service '{}' failed and exit_action=Fail", svc_name);
+                    }
+                    ExitAction::Abort => {
+                        log::warn!("Service '{}' failed; exit_action=Abort, stopping the run.", svc_name);
+                        println!("  exit_action=Abort: stopping the run.");
+                        stop_flag.store(true, Ordering::SeqCst);
+                        report.outcomes.insert(svc_name.clone(), final_outcome);
+                        progress.services.push(svc);
+                        write_progress_file(&progress, &output_path)?;
+                        break;
+                    }
+                }
+            }
+
+            report.outcomes.insert(svc_name.clone(), final_outcome);
         } else {
             println!("  - No end_mode configured, skipping");
         }
 
         // Wait for system CPU to stabilize before processing next service
-        wait_for_cpu_stable(&mut sys, &mut svc, &stop_flag);
+        wait_for_cpu_stable(&mut sys, &mut svc, &stop_flag, &pause_flag, status_handle, &phase_timer);
 
         // Save progress incrementally after each service
         progress.services.push(svc);
@@ -210,10 +388,22 @@ fn run_main(stop_flag: Arc<AtomicBool>) -> Result<()> {
         println!();
     }
 
+    print_report_summary(&report);
+    timing::print_duration_summary(&progress);
     print_footer(&output_path);
     Ok(())
 }
 
+/// Outcome of [`process_service_action`], inspected by [`run_main`] to apply
+/// the service's configured [`ExitAction`] when a transition fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProcessOutcome {
+    /// The service reached (or already was in) its target state.
+    Succeeded,
+    /// The service did not reach its target state within [`MAX_RETRIES`] attempts.
+    Failed,
+}
+
 /// Executes the start or stop action for a service based on its end_mode.
 ///
 /// # Arguments
@@ -223,6 +413,10 @@ fn run_main(stop_flag: Arc<AtomicBool>) -> Result<()> {
 /// * `end_mode` - Target end mode (contains "automatic" to start, otherwise stop)
 /// * `was_running` - Whether the service was running before this action
 /// * `stop_flag` - Graceful shutdown flag
+/// * `status_handle` - SCM status handle for pending-state checkpoints during the
+///   wait; `None` in console mode
+/// * `phase_timer` - Stamps the "stop issued" phase duration (see [`crate::timing`])
+///   once the stop action actually completes
 ///
 /// # Performance Notes
 ///
@@ -234,7 +428,9 @@ fn process_service_action(
     end_mode: &str,
     was_running: bool,
     stop_flag: &Arc<AtomicBool>,
-) {
+    status_handle: Option<ServiceStatusHandle>,
+    phase_timer: &PhaseTimer,
+) -> ProcessOutcome {
     // Determine action based on end_mode (avoid multiple string comparisons)
     let should_start = end_mode.to_lowercase().contains("automatic");
     let timeout_secs = SERVICE_STATE_TIMEOUT.as_secs();
@@ -243,35 +439,166 @@ fn process_service_action(
         if was_running {
             log::info!("Service '{}' already running (target: {}); skipping.", svc_name, end_mode);
             println!("  Already running (target: {})", end_mode);
+            ProcessOutcome::Succeeded
         } else {
             log::info!("Starting '{}' (target: {}).", svc_name, end_mode);
             println!("  Starting service (target: {})...", end_mode);
-            let _ = service_ctrl::run_sc(&["start", svc_name]);
 
-            if service_ctrl::wait_for_service_state_with_stop(svc_name, "RUNNING", timeout_secs, Arc::clone(stop_flag)) {
+            let succeeded = run_with_retry("start", svc_name, "RUNNING", timeout_secs, stop_flag, status_handle);
+            if succeeded {
                 println!("  Started successfully");
             } else {
-                log::warn!("Starting '{}' failed.", svc_name);
+                log::warn!("Starting '{}' failed after {} attempt(s).", svc_name, MAX_RETRIES);
                 println!("  Failed to start");
             }
             svc.record_end();
+            if succeeded { ProcessOutcome::Succeeded } else { ProcessOutcome::Failed }
         }
     } else if !was_running {
         log::info!("Service '{}' already stopped (target: {}); skipping.", svc_name, end_mode);
         println!("  Already stopped (target: {})", end_mode);
+        ProcessOutcome::Succeeded
     } else {
         log::info!("Stopping '{}' (target: {}).", svc_name, end_mode);
         println!("  Stopping service (target: {})...", end_mode);
-        let _ = service_ctrl::run_sc(&["stop", svc_name]);
 
-        if service_ctrl::wait_for_service_state_with_stop(svc_name, "STOPPED", timeout_secs, Arc::clone(stop_flag)) {
+        let succeeded = run_with_retry("stop", svc_name, "STOPPED", timeout_secs, stop_flag, status_handle);
+        if succeeded {
             println!("  Stopped successfully");
         } else {
-            log::warn!("Stopping '{}' failed.", svc_name);
+            log::warn!("Stopping '{}' failed after {} attempt(s).", svc_name, MAX_RETRIES);
             println!("  Failed to stop");
         }
         svc.record_stop();
+        phase_timer.record_stop(svc);
+        if succeeded { ProcessOutcome::Succeeded } else { ProcessOutcome::Failed }
+    }
+}
+
+/// Issues `sc <action> <svc_name>` and waits for `desired_state`, retrying with
+/// NSSM-style exponential backoff (capped at 256s, i.e. `throttle` capped at 8)
+/// up to [`MAX_RETRIES`] attempts when the transition doesn't complete in time.
+///
+/// The backoff counter resets implicitly each call (it's local to this attempt
+/// loop, not persisted on `svc`): a service that fails once and later succeeds
+/// starts its next retry sequence from the base 1s delay, not wherever the
+/// previous attempt left off.
+///
+/// The state-transition wait itself is adaptive (see
+/// [`service_ctrl::wait_for_service_state_adaptive_reporting`]): it extends its
+/// deadline while the target service's own `dwCheckPoint` keeps advancing, so a
+/// service that legitimately takes many minutes to start/stop isn't cut off early.
+/// While that wait is in progress, pushes a rising-checkpoint `StartPending`/
+/// `StopPending` status to the SCM via `status_handle` on every poll (see
+/// [`report_pending`]), so the SCM's own hung-service detection doesn't trip either.
+///
+/// # Returns
+/// `true` if `desired_state` was reached within [`MAX_RETRIES`] attempts,
+/// `false` if every attempt timed out or `stop_flag` was set.
+fn run_with_retry(
+    action: &str,
+    svc_name: &str,
+    desired_state: &str,
+    timeout_secs: u64,
+    stop_flag: &Arc<AtomicBool>,
+    status_handle: Option<ServiceStatusHandle>,
+) -> bool {
+    let mut throttle: u32 = 0;
+
+    for attempt in 1..=MAX_RETRIES {
+        if stop_flag.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        let _ = service_ctrl::run_sc(&[action, svc_name]);
+
+        let stop_flag_for_poll = Arc::clone(stop_flag);
+        let reached = service_ctrl::wait_for_service_state_adaptive_reporting(
+            svc_name,
+            desired_state,
+            timeout_secs,
+            service_ctrl::DEFAULT_WAIT_HINT_FLOOR,
+            Arc::clone(stop_flag),
+            |checkpoint| {
+                report_pending(
+                    status_handle,
+                    &stop_flag_for_poll,
+                    checkpoint,
+                    service_ctrl::POLL_INTERVAL + SCM_WAIT_HINT_MARGIN,
+                )
+            },
+        );
+        if !stop_flag.load(Ordering::SeqCst) {
+            report_status(status_handle, ServiceState::Running);
+        }
+        if reached {
+            return true;
+        }
+
+        if attempt == MAX_RETRIES {
+            break;
+        }
+
+        let ms = (1u64 << throttle.min(8)) * 1000;
+        throttle += 1;
+        log::warn!(
+            "Attempt {}/{} to {} '{}' failed; retrying in {}s.",
+            attempt, MAX_RETRIES, action, svc_name, ms / 1000
+        );
+        println!("  Attempt {}/{} failed; retrying in {}s...", attempt, MAX_RETRIES, ms / 1000);
+
+        if !sleep_honoring_stop(Duration::from_millis(ms), stop_flag) {
+            return false;
+        }
+    }
+
+    false
+}
+
+/// Sleeps for `duration`, polling `stop_flag` every [`BACKOFF_POLL_INTERVAL`] so
+/// a shutdown request interrupts the wait promptly instead of only after it
+/// elapses.
+///
+/// # Returns
+/// `true` if the full duration elapsed, `false` if `stop_flag` was set first.
+fn sleep_honoring_stop(duration: Duration, stop_flag: &Arc<AtomicBool>) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        if stop_flag.load(Ordering::SeqCst) {
+            return false;
+        }
+        sleep(BACKOFF_POLL_INTERVAL.min(duration - start.elapsed()));
     }
+    true
+}
+
+/// Suspends the caller while `pause_flag` is set, reporting `ServiceState::Paused`
+/// to the SCM for the duration and `ServiceState::Running` once lifted. Polls
+/// every [`PAUSE_POLL_INTERVAL`] so a `Continue` or `Stop` is noticed promptly.
+///
+/// A no-op if `pause_flag` isn't set when called.
+fn wait_while_paused(
+    pause_flag: &Arc<AtomicBool>,
+    stop_flag: &Arc<AtomicBool>,
+    status_handle: Option<ServiceStatusHandle>,
+) {
+    if !pause_flag.load(Ordering::SeqCst) {
+        return;
+    }
+
+    log::info!("Pause requested; suspending between services.");
+    println!("  Paused; waiting for Continue...");
+    report_status(status_handle, ServiceState::Paused);
+
+    while pause_flag.load(Ordering::SeqCst) {
+        if stop_flag.load(Ordering::SeqCst) {
+            return;
+        }
+        sleep(PAUSE_POLL_INTERVAL);
+    }
+
+    report_status(status_handle, ServiceState::Running);
+    println!("  Resumed");
 }
 
 /// Waits for CPU usage to drop below the threshold before continuing.
@@ -283,14 +610,23 @@ fn process_service_action(
 /// # Arguments
 ///
 /// * `sys` - System info handle for CPU monitoring (reused across calls for efficiency)
-/// * `svc` - Service entry to record CPU responsive timestamp
+/// * `svc` - Service entry to record CPU responsive timestamp; also supplies the
+///   effective threshold/poll interval/timeout via
+///   [`ServiceEntry::cpu_threshold`]/[`ServiceEntry::cpu_poll_interval`]/
+///   [`ServiceEntry::cpu_wait_timeout`][progresso_service::ServiceEntry], falling
+///   back to the library's `DEFAULT_CPU_*` defaults when unset in the XML
 /// * `stop_flag` - Graceful shutdown flag for early termination
+/// * `pause_flag` - `Pause` control flag; suspends polling via [`wait_while_paused`]
+///   between iterations
+/// * `status_handle` - SCM status handle passed through to [`wait_while_paused`]
+/// * `phase_timer` - Stamps the "CPU responsive" phase duration (see
+///   [`crate::timing`]) alongside the timestamp, whichever way the wait ends
 ///
 /// # Behavior
 ///
-/// - Polls CPU usage every second
+/// - Polls CPU usage at `svc`'s effective poll interval (default: every second)
 /// - Reports only when usage changes by â‰¥5% (see [`CPU_REPORT_DELTA`])
-/// - Times out after 5 minutes (see [`CPU_WAIT_TIMEOUT`])
+/// - Times out after `svc`'s effective timeout (default: 5 minutes)
 /// - Records timestamp when CPU drops below threshold or on timeout/cancellation
 ///
 /// # Performance Notes
@@ -301,11 +637,19 @@ fn wait_for_cpu_stable(
     sys: &mut System,
     svc: &mut progresso_service::ServiceEntry,
     stop_flag: &Arc<AtomicBool>,
+    pause_flag: &Arc<AtomicBool>,
+    status_handle: Option<ServiceStatusHandle>,
+    phase_timer: &PhaseTimer,
 ) {
-    println!("  Waiting for CPU below {}%...", CPU_THRESHOLD);
+    let threshold = svc.cpu_threshold();
+    let poll_interval = svc.cpu_poll_interval();
+    let wait_timeout = svc.cpu_wait_timeout();
+
+    println!("  Waiting for CPU below {}%...", threshold);
 
     let start_wait = Instant::now();
     let mut last_reported_usage = -1.0_f32;
+    let mut checkpoint: u32 = 0;
 
     loop {
         // Check for shutdown request (fast path - atomic load)
@@ -313,6 +657,14 @@ fn wait_for_cpu_stable(
             log::info!("Stop requested while waiting for CPU");
             println!("  Stop requested");
             svc.record_cpu_responsive();
+            phase_timer.record_cpu_responsive(svc);
+            break;
+        }
+
+        wait_while_paused(pause_flag, stop_flag, status_handle);
+        if stop_flag.load(Ordering::SeqCst) {
+            svc.record_cpu_responsive();
+            phase_timer.record_cpu_responsive(svc);
             break;
         }
 
@@ -326,25 +678,34 @@ fn wait_for_cpu_stable(
         }
 
         // Check threshold (success case)
-        if usage < CPU_THRESHOLD {
+        if usage < threshold {
             println!("  CPU below threshold ({:.1}%)", usage);
             svc.record_cpu_responsive();
+            phase_timer.record_cpu_responsive(svc);
             break;
         }
 
         // Check timeout (failure case - CPU still high)
-        if start_wait.elapsed() > CPU_WAIT_TIMEOUT {
+        if start_wait.elapsed() > wait_timeout {
             log::warn!(
                 "CPU wait timeout reached after {} seconds (current: {:.1}%)",
-                CPU_WAIT_TIMEOUT.as_secs(),
+                wait_timeout.as_secs(),
                 usage
             );
             println!("  CPU wait timeout reached ({:.1}%)", usage);
             svc.record_cpu_responsive();
+            phase_timer.record_cpu_responsive(svc);
             break;
         }
 
-        sleep(CPU_POLL_INTERVAL);
+        checkpoint += 1;
+        report_pending(status_handle, stop_flag, checkpoint, poll_interval + SCM_WAIT_HINT_MARGIN);
+
+        sleep(poll_interval);
+    }
+
+    if !stop_flag.load(Ordering::SeqCst) {
+        report_status(status_handle, ServiceState::Running);
     }
 }
 
@@ -365,6 +726,15 @@ fn print_footer(output_path: &str) {
     println!("========================================\n");
 }
 
+/// Prints an aggregate summary of per-service [`ServiceOutcome`]s collected while
+/// processing the run's dependency-ordered services.
+#[inline]
+fn print_report_summary(report: &EngineReport) {
+    let started = report.outcomes.values().filter(|o| **o == ServiceOutcome::Started).count();
+    let timed_out = report.outcomes.values().filter(|o| **o == ServiceOutcome::TimedOut).count();
+    println!("Summary: {} started, {} timed out (of {} tracked)", started, timed_out, report.outcomes.len());
+}
+
 /// Writes the current progress data to an XML file with proper formatting.
 ///
 /// Creates a complete XML document with declaration header and serialized progress data.
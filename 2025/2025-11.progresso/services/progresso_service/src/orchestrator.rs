@@ -0,0 +1,127 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Async Service Lifecycle Orchestrator
+//
+// The crate models service configs and timestamps but, until now, had no engine that
+// actually drove start/stop of the services in an `OrdemTargets`. `ServiceOrchestrator`
+// fills that gap, modeled on eva-ics's launcher pattern: each service's start/stop is
+// issued under a bounded timeout (the caller-supplied per-service timeout plus a small
+// fixed slack, the same way eva-ics adds a second to let the transport call complete),
+// retried with backoff on timeout or failure, and every transition is recorded via the
+// matching `ServiceEntry::record_*` method.
+
+use std::time::Duration;
+
+use crate::service_ctrl::{ServiceController, ServiceState};
+use crate::{OrdemTargets, ServiceEntry};
+
+/// Extra slack added to the caller-supplied per-service timeout, to let the transport
+/// call (e.g. `sc start`) itself complete before the wait is judged to have timed out.
+const TIMEOUT_SLACK: Duration = Duration::from_secs(1);
+
+/// Interval at which [`ServiceOrchestrator`] polls for the desired state.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Outcome of orchestrating a single service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceResult {
+    /// The service reached its desired end state.
+    Succeeded,
+    /// Every retry was used, and on the final attempt the start/stop command
+    /// succeeded but the state transition never completed within the timeout.
+    TimedOut,
+    /// Every retry was used, and on the final attempt the start/stop command itself
+    /// failed.
+    FailedAfterRetries,
+}
+
+/// Drives start/stop of every service in an `OrdemTargets`, retrying with backoff and
+/// recording lifecycle timestamps, so the resulting `OrdemTargets` is a complete
+/// progress report that [`crate::write_progress_xml`] can serialize.
+pub struct ServiceOrchestrator<'a> {
+    controller: &'a dyn ServiceController,
+    timeout: Duration,
+    max_retries: u32,
+}
+
+impl<'a> ServiceOrchestrator<'a> {
+    /// Creates an orchestrator that waits up to `timeout` (plus [`TIMEOUT_SLACK`]) per
+    /// attempt, retrying up to `max_retries` times with exponential backoff.
+    pub fn new(controller: &'a dyn ServiceController, timeout: Duration, max_retries: u32) -> Self {
+        Self { controller, timeout, max_retries }
+    }
+
+    /// Processes every service in `ordem` in order, returning a per-service result
+    /// summary in the same order.
+    pub async fn run(&self, ordem: &mut OrdemTargets) -> Vec<(String, ServiceResult)> {
+        let mut results = Vec::with_capacity(ordem.services.len());
+        for svc in &mut ordem.services {
+            let Some(name) = svc.name().map(str::to_string) else { continue };
+            let result = self.process_one(svc, &name).await;
+            results.push((name, result));
+        }
+        results
+    }
+
+    /// Issues the start/stop decided by [`ServiceEntry::should_start`], retrying on
+    /// failure or timeout, and records the transition's timestamp.
+    async fn process_one(&self, svc: &mut ServiceEntry, name: &str) -> ServiceResult {
+        svc.record_start_processing();
+        let should_start = svc.should_start();
+        let desired = if should_start { ServiceState::Running } else { ServiceState::Stopped };
+
+        let mut attempt = 0u32;
+        loop {
+            let command_failed = if should_start {
+                self.controller.start(name).is_err()
+            } else {
+                self.controller.stop(name).is_err()
+            };
+
+            let reached = if command_failed {
+                false
+            } else {
+                tokio::time::timeout(self.timeout + TIMEOUT_SLACK, self.wait_until(name, desired))
+                    .await
+                    .unwrap_or(false)
+            };
+
+            if reached {
+                Self::record_transition(svc, should_start);
+                return ServiceResult::Succeeded;
+            }
+
+            attempt += 1;
+            if attempt > self.max_retries {
+                Self::record_transition(svc, should_start);
+                return if command_failed {
+                    ServiceResult::FailedAfterRetries
+                } else {
+                    ServiceResult::TimedOut
+                };
+            }
+
+            tokio::time::sleep(Duration::from_secs(1u64 << attempt.min(6))).await;
+        }
+    }
+
+    /// Polls `name` until it reports `desired`.
+    async fn wait_until(&self, name: &str, desired: ServiceState) -> bool {
+        loop {
+            if let Ok(state) = self.controller.query(name) {
+                if state == desired {
+                    return true;
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    fn record_transition(svc: &mut ServiceEntry, should_start: bool) {
+        if should_start {
+            svc.record_end();
+        } else {
+            svc.record_stop();
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Registry Run-Key Autostart Backend
+//
+// An alternative to SCM-managed services: registers a command under
+// `HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Run` so a
+// `ServiceEntry` can be launched without administrator rights, a stored
+// username/password, or SCM policy interference.
+//
+// Because the OS does not manage these processes the way it manages a real service,
+// `register_run_key` also spawns the process immediately, and `unregister_run_key`
+// terminates the matching process tree.
+
+#[cfg(windows)]
+mod imp {
+    use std::io;
+    use std::path::Path;
+    use std::process::Command;
+
+    use winreg::enums::{HKEY_CURRENT_USER, KEY_QUERY_VALUE, KEY_SET_VALUE};
+    use winreg::RegKey;
+
+    use crate::ServiceEntry;
+
+    const RUN_KEY_PATH: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    /// Registers `entry` under the Run key and spawns it immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `entry` has no `name`/`path`, the registry write fails, or
+    /// the process fails to spawn.
+    pub fn register_run_key(entry: &ServiceEntry) -> io::Result<()> {
+        let name = entry
+            .name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "entry has no name"))?;
+        let path = entry
+            .path
+            .as_deref()
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "entry has no path"))?;
+
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let (key, _) = hkcu.create_subkey(RUN_KEY_PATH)?;
+        key.set_value(name, &path)?;
+
+        Command::new(path).spawn()?;
+        Ok(())
+    }
+
+    /// Removes `name` from the Run key and terminates its process tree, since
+    /// unregistering alone would leave an already-running instance orphaned.
+    pub fn unregister_run_key(name: &str) -> io::Result<()> {
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let key = hkcu.open_subkey_with_flags(RUN_KEY_PATH, KEY_SET_VALUE | KEY_QUERY_VALUE)?;
+
+        // Look up the registered path before deleting the value, so we know which
+        // image to terminate.
+        let path: Option<String> = key.get_value(name).ok();
+        key.delete_value(name)?;
+
+        if let Some(exe) = path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_name())
+            .and_then(|f| f.to_str())
+        {
+            // Best-effort: the process may have already exited.
+            let _ = Command::new("taskkill").args(["/F", "/T", "/IM", exe]).output();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+mod imp {
+    use std::io;
+
+    use crate::ServiceEntry;
+
+    /// Stub for non-Windows builds: the Run key is a Windows-only concept.
+    pub fn register_run_key(_entry: &ServiceEntry) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Run-key autostart is only supported on Windows",
+        ))
+    }
+
+    /// Stub for non-Windows builds: the Run key is a Windows-only concept.
+    pub fn unregister_run_key(_name: &str) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Run-key autostart is only supported on Windows",
+        ))
+    }
+}
+
+pub use imp::{register_run_key, unregister_run_key};
@@ -15,16 +15,224 @@
 // - run_sc: Execute arbitrary `sc` commands
 // - is_service_running: Check if a service is in RUNNING state
 // - wait_for_service_state_with_stop: Poll until a service reaches a desired state
+// - wait_for_service_state_adaptive: Like the above, but sized to the service's own
+//   reported dwCheckPoint/dwWaitHint instead of a single fixed timeout
+//
+// ## Testability
+//
+// All of the above except `run_sc` are thin wrappers around the [`ServiceController`] trait, which
+// abstracts *how* a service's state is queried/changed. Production code uses the
+// default [`ScController`] (which shells out to `sc.exe`); tests use [`MockController`]
+// to script state transitions entirely in memory, with no subprocess spawning and no
+// process-wide state (e.g. `PATH`) to mutate.
 
+use std::collections::{HashMap, VecDeque};
 use std::process::{Command, Output};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use std::thread::sleep;
 use std::io;
 
 /// Polling interval for service state checks.
-const POLL_INTERVAL: Duration = Duration::from_secs(1);
+///
+/// Also reused by [`crate::service_log`] for its log-tailing poll loop, since both are
+/// "cheap to poll every second" concerns, and by `progresso_service`'s `main.rs` to size
+/// the `wait_hint` it reports to the SCM while a [`wait_for_adaptive_reporting`] wait is
+/// in progress.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Abstracts the passage of time so waits can be tested deterministically.
+///
+/// Production code uses [`RealTimeSource`], which wraps [`Instant::now`] and
+/// [`std::thread::sleep`]. Tests inject [`MockTimeSource`], which advances virtual time
+/// instantly instead of actually sleeping.
+pub trait TimeSource {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+    /// Blocks (or, for a mock, advances virtual time) for `dur`.
+    fn sleep(&self, dur: Duration);
+}
+
+/// Default [`TimeSource`] backed by the real wall clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealTimeSource;
+
+impl TimeSource for RealTimeSource {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, dur: Duration) {
+        sleep(dur);
+    }
+}
+
+/// In-memory [`TimeSource`] for tests: `sleep` advances a virtual clock instead of
+/// blocking, so a timeout loop completes instantly in wall-clock time while duration
+/// math (`now().duration_since(start)`) still behaves correctly.
+///
+/// Also counts how many times `sleep` was called, so a test can assert a wait loop
+/// polled a controlled number of times before giving up.
+#[derive(Debug)]
+pub struct MockTimeSource {
+    base: Instant,
+    elapsed: Mutex<Duration>,
+    sleep_count: AtomicUsize,
+}
+
+impl MockTimeSource {
+    /// Creates a `MockTimeSource` anchored at the current real instant.
+    pub fn new() -> Self {
+        Self {
+            base: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+            sleep_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns how many times `sleep` has been called so far.
+    pub fn sleep_count(&self) -> usize {
+        self.sleep_count.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for MockTimeSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSource for MockTimeSource {
+    fn now(&self) -> Instant {
+        self.base + *self.elapsed.lock().unwrap()
+    }
+
+    fn sleep(&self, dur: Duration) {
+        self.sleep_count.fetch_add(1, Ordering::SeqCst);
+        *self.elapsed.lock().unwrap() += dur;
+    }
+}
+
+/// Observed state of a Windows service, as reported by a [`ServiceController`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceState {
+    Running,
+    Stopped,
+    Unknown,
+}
+
+impl ServiceState {
+    /// Returns `true` if `desired` (e.g. `"RUNNING"`, `"STOPPED"`) names this state,
+    /// compared case-insensitively.
+    #[inline]
+    pub fn matches(&self, desired: &str) -> bool {
+        let name = match self {
+            ServiceState::Running => "running",
+            ServiceState::Stopped => "stopped",
+            ServiceState::Unknown => "unknown",
+        };
+        desired.eq_ignore_ascii_case(name)
+    }
+}
+
+/// A service's state together with the `dwCheckPoint`/`dwWaitHint` it reports while
+/// mid-transition (`sc queryex`'s `CHECKPOINT`/`WAIT_HINT` fields), used by
+/// [`wait_for_adaptive`] to size its per-checkpoint deadline instead of a single
+/// fixed timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceStatusDetail {
+    /// The service's resolved state.
+    pub state: ServiceState,
+    /// Monotonically-increasing progress counter the service advances while
+    /// mid-transition; `0` outside a pending transition or when unsupported.
+    pub checkpoint: u32,
+    /// How much longer the service expects the current transition to take.
+    pub wait_hint: Duration,
+}
+
+/// Abstracts querying and driving Windows service state.
+///
+/// Production code uses [`ScController`], which shells out to `sc.exe`. Tests inject
+/// [`MockController`] to script state transitions without spawning processes.
+pub trait ServiceController: Send + Sync {
+    /// Queries the current state of a service.
+    fn query(&self, name: &str) -> io::Result<ServiceState>;
+    /// Issues a start command for a service.
+    fn start(&self, name: &str) -> io::Result<()>;
+    /// Issues a stop command for a service.
+    fn stop(&self, name: &str) -> io::Result<()>;
+
+    /// Like [`Self::query`], but also reports the `dwCheckPoint`/`dwWaitHint` a
+    /// service advertises while mid-transition. The default implementation reports
+    /// a stationary `checkpoint` of `0` and a zero `wait_hint`, appropriate for
+    /// controllers that don't model pending-transition detail.
+    fn query_detailed(&self, name: &str) -> io::Result<ServiceStatusDetail> {
+        Ok(ServiceStatusDetail { state: self.query(name)?, checkpoint: 0, wait_hint: Duration::ZERO })
+    }
+}
+
+/// Default [`ServiceController`] implementation that shells out to `sc.exe`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ScController;
+
+impl ServiceController for ScController {
+    fn query(&self, name: &str) -> io::Result<ServiceState> {
+        let output = run_sc(&["query", name])?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
+        Ok(if stdout.contains("running") {
+            ServiceState::Running
+        } else if stdout.contains("stopped") {
+            ServiceState::Stopped
+        } else {
+            ServiceState::Unknown
+        })
+    }
+
+    fn start(&self, name: &str) -> io::Result<()> {
+        run_sc(&["start", name]).map(|_| ())
+    }
+
+    fn stop(&self, name: &str) -> io::Result<()> {
+        run_sc(&["stop", name]).map(|_| ())
+    }
+
+    fn query_detailed(&self, name: &str) -> io::Result<ServiceStatusDetail> {
+        let output = run_sc(&["queryex", name])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lower = stdout.to_lowercase();
+        let state = if lower.contains("running") {
+            ServiceState::Running
+        } else if lower.contains("stopped") {
+            ServiceState::Stopped
+        } else {
+            ServiceState::Unknown
+        };
+        let checkpoint = parse_hex_field(&stdout, "CHECKPOINT");
+        let wait_hint_ms = parse_hex_field(&stdout, "WAIT_HINT");
+        Ok(ServiceStatusDetail { state, checkpoint, wait_hint: Duration::from_millis(wait_hint_ms as u64) })
+    }
+}
+
+/// Parses the hex value (e.g. `0x1388`) out of an `sc queryex` line starting with
+/// `label` (e.g. `"CHECKPOINT"`, `"WAIT_HINT"`). Returns `0` if `label` isn't found
+/// or its value doesn't parse, so a missing/unexpected field degrades to "no
+/// progress reported" rather than failing the query.
+fn parse_hex_field(stdout: &str, label: &str) -> u32 {
+    stdout
+        .lines()
+        .find_map(|line| {
+            let line = line.trim_start();
+            if !line.starts_with(label) {
+                return None;
+            }
+            let value = line.split(':').nth(1)?.trim();
+            let hex = value.trim_start_matches("0x").trim_start_matches("0X");
+            u32::from_str_radix(hex, 16).ok()
+        })
+        .unwrap_or(0)
+}
 
 /// Executes the Windows `sc.exe` command with the provided arguments.
 ///
@@ -60,8 +268,8 @@ pub fn run_sc(args: &[&str]) -> io::Result<Output> {
 
 /// Checks if a Windows service is currently in the RUNNING state.
 ///
-/// Queries the service using `sc query` and parses the output to determine
-/// if the service is running.
+/// Delegates to the default [`ScController`]; see [`is_service_running_with`] to
+/// inject a different controller (e.g. [`MockController`] in tests).
 ///
 /// # Arguments
 ///
@@ -83,24 +291,18 @@ pub fn run_sc(args: &[&str]) -> io::Result<Output> {
 /// ```
 #[inline]
 pub fn is_service_running(name: &str) -> bool {
-    Command::new("sc")
-        .args(["query", name])
-        .output()
-        .ok()
-        .map(|out| {
-            String::from_utf8_lossy(&out.stdout)
-                .to_lowercase()
-                .contains("running")
-        })
-        .unwrap_or(false)
+    is_service_running_with(&ScController, name)
+}
+
+/// Like [`is_service_running`], but queries through an injected [`ServiceController`].
+pub fn is_service_running_with(controller: &dyn ServiceController, name: &str) -> bool {
+    matches!(controller.query(name), Ok(ServiceState::Running))
 }
 
 /// Waits for a service to reach a desired state with timeout and cancellation support.
 ///
-/// Polls the service state at 1-second intervals until either:
-/// - The desired state is reached (returns `true`)
-/// - The timeout expires (returns `false`)
-/// - The stop flag is set (returns `false`)
+/// Delegates to the default [`ScController`]; see [`wait_for_state_with`] to inject a
+/// different controller (e.g. [`MockController`] in tests).
 ///
 /// # Arguments
 ///
@@ -135,26 +337,277 @@ pub fn wait_for_service_state_with_stop(
     timeout_secs: u64,
     stop_flag: Arc<AtomicBool>,
 ) -> bool {
-    let start = Instant::now();
-    let desired_lower = desired.to_lowercase();
+    wait_for_state_with(&ScController, name, desired, timeout_secs, stop_flag)
+}
+
+/// Like [`wait_for_service_state_with_stop`], but invokes `on_poll` with an
+/// incrementing checkpoint number on every poll that doesn't yet see `desired`,
+/// so the caller can push an SCM pending-state checkpoint during a long
+/// transition. See [`wait_for_state_reporting`].
+pub fn wait_for_service_state_with_stop_reporting(
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    stop_flag: Arc<AtomicBool>,
+    on_poll: impl FnMut(u32),
+) -> bool {
+    wait_for_state_reporting(&ScController, &RealTimeSource, name, desired, timeout_secs, stop_flag, on_poll)
+}
+
+/// Like [`wait_for_service_state_with_stop`], but polls through an injected
+/// [`ServiceController`] instead of always shelling out to `sc.exe`.
+///
+/// Uses the real clock ([`RealTimeSource`]); see [`wait_for_state`] to also inject a
+/// [`TimeSource`] (e.g. [`MockTimeSource`] in tests).
+pub fn wait_for_state_with(
+    controller: &dyn ServiceController,
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    stop_flag: Arc<AtomicBool>,
+) -> bool {
+    wait_for_state(controller, &RealTimeSource, name, desired, timeout_secs, stop_flag)
+}
+
+/// Like [`wait_for_state_with`], but also takes an injected [`TimeSource`] so the
+/// polling loop's timing can be driven deterministically in tests.
+pub fn wait_for_state(
+    controller: &dyn ServiceController,
+    time: &dyn TimeSource,
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    stop_flag: Arc<AtomicBool>,
+) -> bool {
+    wait_for_state_reporting(controller, time, name, desired, timeout_secs, stop_flag, |_checkpoint| {})
+}
+
+/// Like [`wait_for_state`], but invokes `on_poll` with an incrementing checkpoint
+/// number after every unsuccessful query, before the poll sleep. Callers use this to
+/// push SCM pending-state checkpoints during a long state transition so the SCM
+/// doesn't consider the service hung (`progresso_service`'s `main.rs` does the same
+/// through [`wait_for_adaptive_reporting`]).
+///
+/// `on_poll` is never called once the desired state is reached or the wait is
+/// cancelled via `stop_flag`.
+pub fn wait_for_state_reporting(
+    controller: &dyn ServiceController,
+    time: &dyn TimeSource,
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    stop_flag: Arc<AtomicBool>,
+    mut on_poll: impl FnMut(u32),
+) -> bool {
+    let start = time.now();
     let timeout = Duration::from_secs(timeout_secs);
+    let mut checkpoint: u32 = 0;
 
-    while start.elapsed() < timeout {
+    while time.now().duration_since(start) < timeout {
         // Check for cancellation request
         if stop_flag.load(Ordering::SeqCst) {
             return false;
         }
 
         // Query current service state
-        if let Ok(output) = Command::new("sc").args(["query", name]).output() {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
-            if stdout.contains(&desired_lower) {
+        if let Ok(state) = controller.query(name) {
+            if state.matches(desired) {
                 return true;
             }
         }
 
-        sleep(POLL_INTERVAL);
+        checkpoint += 1;
+        on_poll(checkpoint);
+
+        time.sleep(POLL_INTERVAL);
     }
 
     false
 }
+
+/// Default floor under a service's self-reported `wait_hint` when deciding how long
+/// to wait for its next checkpoint, matching Puppet's Windows service provider
+/// behavior: some services report an unrealistically small `dwWaitHint`, so treat
+/// anything below this floor as untrustworthy.
+pub const DEFAULT_WAIT_HINT_FLOOR: Duration = Duration::from_secs(30);
+
+/// Waits for a service to reach `desired`, adapting the per-checkpoint deadline to
+/// the service's own reported `dwWaitHint`/`dwCheckPoint` instead of a single fixed
+/// timeout.
+///
+/// Delegates to the default [`ScController`] and [`RealTimeSource`]; see
+/// [`wait_for_adaptive`] to inject both for testing.
+///
+/// # Arguments
+///
+/// * `timeout_secs` - Absolute ceiling across the whole wait: reached regardless of
+///   how many checkpoints advance, so a service that reports progress forever can't
+///   hang this call indefinitely.
+/// * `wait_hint_floor` - Minimum time to wait after each checkpoint advance, even if
+///   the service's own `dwWaitHint` is smaller (see [`DEFAULT_WAIT_HINT_FLOOR`]).
+pub fn wait_for_service_state_adaptive(
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    wait_hint_floor: Duration,
+    stop_flag: Arc<AtomicBool>,
+) -> bool {
+    wait_for_adaptive(&ScController, &RealTimeSource, name, desired, timeout_secs, wait_hint_floor, stop_flag)
+}
+
+/// Like [`wait_for_service_state_adaptive`], but also invokes `on_poll` with an
+/// incrementing poll counter on every poll that doesn't yet see `desired`, so the
+/// caller can push an SCM pending-state checkpoint during a long transition.
+pub fn wait_for_service_state_adaptive_reporting(
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    wait_hint_floor: Duration,
+    stop_flag: Arc<AtomicBool>,
+    on_poll: impl FnMut(u32),
+) -> bool {
+    wait_for_adaptive_reporting(
+        &ScController,
+        &RealTimeSource,
+        name,
+        desired,
+        timeout_secs,
+        wait_hint_floor,
+        stop_flag,
+        on_poll,
+    )
+}
+
+/// Like [`wait_for_service_state_adaptive`], but polls through an injected
+/// [`ServiceController`] and [`TimeSource`] so the deadline logic can be tested
+/// deterministically.
+pub fn wait_for_adaptive(
+    controller: &dyn ServiceController,
+    time: &dyn TimeSource,
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    wait_hint_floor: Duration,
+    stop_flag: Arc<AtomicBool>,
+) -> bool {
+    wait_for_adaptive_reporting(controller, time, name, desired, timeout_secs, wait_hint_floor, stop_flag, |_| {})
+}
+
+/// Like [`wait_for_adaptive`], but invokes `on_poll` with an incrementing poll
+/// counter after every unsuccessful query, before the poll sleep.
+///
+/// On each poll: if the service's `dwCheckPoint` advanced since the last poll, the
+/// deadline resets to `now + max(wait_hint, wait_hint_floor)`; otherwise the
+/// deadline from the last advance still applies, and the wait fails once it's
+/// passed. `timeout_secs` is checked independently as an absolute ceiling.
+pub fn wait_for_adaptive_reporting(
+    controller: &dyn ServiceController,
+    time: &dyn TimeSource,
+    name: &str,
+    desired: &str,
+    timeout_secs: u64,
+    wait_hint_floor: Duration,
+    stop_flag: Arc<AtomicBool>,
+    mut on_poll: impl FnMut(u32),
+) -> bool {
+    let start = time.now();
+    let ceiling = Duration::from_secs(timeout_secs);
+    let mut last_checkpoint: u32 = 0;
+    let mut deadline = start + wait_hint_floor;
+    let mut poll_count: u32 = 0;
+
+    loop {
+        if stop_flag.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        let now = time.now();
+        if now.duration_since(start) >= ceiling {
+            return false;
+        }
+
+        match controller.query_detailed(name) {
+            Ok(detail) if detail.state.matches(desired) => return true,
+            Ok(detail) if detail.checkpoint > last_checkpoint => {
+                last_checkpoint = detail.checkpoint;
+                deadline = now + detail.wait_hint.max(wait_hint_floor);
+            }
+            _ if now >= deadline => return false,
+            _ => {}
+        }
+
+        poll_count += 1;
+        on_poll(poll_count);
+
+        time.sleep(POLL_INTERVAL);
+    }
+}
+
+/// In-memory [`ServiceController`] for tests: maps service names to scripted state
+/// sequences instead of spawning subprocesses.
+///
+/// Each call to [`MockController::query`] for a given service pops the next state off
+/// its scripted queue, repeating the last scripted state once the queue is drained (so
+/// a timeout test can poll it indefinitely without panicking). `start`/`stop` are no-ops
+/// that always succeed; tests assert on the state transitions they script, not on
+/// whether a command was "sent".
+#[derive(Debug, Default)]
+pub struct MockController {
+    states: Mutex<HashMap<String, VecDeque<ServiceState>>>,
+    details: Mutex<HashMap<String, VecDeque<ServiceStatusDetail>>>,
+}
+
+impl MockController {
+    /// Creates a `MockController` with no scripted services.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scripts the sequence of states `query` will report for `name`, in order.
+    pub fn script(&self, name: &str, states: impl IntoIterator<Item = ServiceState>) {
+        let mut map = self.states.lock().unwrap();
+        map.insert(name.to_string(), states.into_iter().collect());
+    }
+
+    /// Scripts the sequence of [`ServiceStatusDetail`] (state + checkpoint/wait_hint)
+    /// `query_detailed` will report for `name`, in order. Use this instead of
+    /// [`Self::script`] to exercise [`wait_for_adaptive`]'s checkpoint-tracking logic.
+    pub fn script_detailed(&self, name: &str, details: impl IntoIterator<Item = ServiceStatusDetail>) {
+        let mut map = self.details.lock().unwrap();
+        map.insert(name.to_string(), details.into_iter().collect());
+    }
+}
+
+impl ServiceController for MockController {
+    fn query(&self, name: &str) -> io::Result<ServiceState> {
+        let mut map = self.states.lock().unwrap();
+        let queue = map.entry(name.to_string()).or_default();
+        if queue.len() > 1 {
+            Ok(queue.pop_front().unwrap())
+        } else {
+            Ok(queue.front().copied().unwrap_or(ServiceState::Unknown))
+        }
+    }
+
+    fn start(&self, _name: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn stop(&self, _name: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn query_detailed(&self, name: &str) -> io::Result<ServiceStatusDetail> {
+        let mut map = self.details.lock().unwrap();
+        if let Some(queue) = map.get_mut(name) {
+            if !queue.is_empty() {
+                return Ok(if queue.len() > 1 {
+                    queue.pop_front().unwrap()
+                } else {
+                    *queue.front().unwrap()
+                });
+            }
+        }
+        Ok(ServiceStatusDetail { state: self.query(name)?, checkpoint: 0, wait_hint: Duration::ZERO })
+    }
+}
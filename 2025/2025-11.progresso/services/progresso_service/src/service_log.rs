@@ -0,0 +1,125 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Service Log Tailing
+//
+// Captures and follows a service's output the way a tunnel CLI's `service log`
+// command does: each service gets a log file under a data directory, and
+// `tail` follows it by polling the file's length rather than pulling in a
+// filesystem-watcher dependency. This module only appends to the log file; it
+// never rotates or size-limits it.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "linux")]
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+
+use crate::service_ctrl::POLL_INTERVAL;
+
+/// Returns the path of the log file for `service_name` under `data_dir`.
+#[inline]
+pub fn log_file_path(data_dir: &Path, service_name: &str) -> PathBuf {
+    data_dir.join(format!("{service_name}.log"))
+}
+
+/// Opens (creating if needed) the log file that a spawned service's stdout/stderr
+/// should be redirected into.
+///
+/// Intended for backends that spawn the service process directly (e.g. a Run-key
+/// autostart backend) rather than handing it to the Windows SCM, which manages a
+/// service's output itself.
+pub fn open_for_redirect(data_dir: &Path, service_name: &str) -> io::Result<File> {
+    fs::create_dir_all(data_dir)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_file_path(data_dir, service_name))
+}
+
+/// Returns `true` if `service_name` is registered as a systemd unit, in which case its
+/// logs are best tailed via `journalctl` instead of a local log file.
+#[cfg(target_os = "linux")]
+pub fn is_systemd_unit(service_name: &str) -> bool {
+    Command::new("systemctl")
+        .args(["status", service_name])
+        .output()
+        .map(|out| out.status.code() != Some(4)) // exit code 4: unit not found
+        .unwrap_or(false)
+}
+
+/// Tails a service's log output, printing appended bytes to stdout.
+///
+/// On Linux, if `service_name` is registered with systemd, delegates to
+/// `journalctl -u <name>` (with `-f` when `follow` is requested). Otherwise follows
+/// the log file under `data_dir` by polling its length every
+/// [`POLL_INTERVAL`] and emitting only the bytes appended since the last read.
+///
+/// When `follow` is `false`, prints the current contents once and returns.
+pub fn tail(data_dir: &Path, service_name: &str, follow: bool) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        if is_systemd_unit(service_name) {
+            return tail_journalctl(service_name, follow);
+        }
+    }
+
+    tail_file(&log_file_path(data_dir, service_name), follow)
+}
+
+/// Delegates to `journalctl -u <name>` for services registered with systemd.
+#[cfg(target_os = "linux")]
+fn tail_journalctl(service_name: &str, follow: bool) -> io::Result<()> {
+    let mut args = vec!["-u", service_name];
+    if follow {
+        args.push("-f");
+    }
+
+    let status = Command::new("journalctl")
+        .args(&args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()?;
+
+    if !status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("journalctl exited with {status}"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Follows a single log file by polling its length, emitting only newly appended
+/// bytes since the last read offset.
+///
+/// Detects truncation/rotation by checking whether the current length has dropped
+/// below the saved offset, in which case it resets to the start of the file.
+fn tail_file(path: &Path, follow: bool) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut offset = 0u64;
+
+    loop {
+        let len = file.metadata()?.len();
+
+        // Truncation/rotation: the file is shorter than what we've already read.
+        if len < offset {
+            offset = 0;
+        }
+
+        if len > offset {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut buf = vec![0u8; (len - offset) as usize];
+            file.read_exact(&mut buf)?;
+            io::stdout().write_all(&buf)?;
+            offset = len;
+        }
+
+        if !follow {
+            return Ok(());
+        }
+
+        sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Service Supervisor
+//
+// Watches a started service's state via an injected `ServiceController` and enforces
+// its `RestartPolicy` when it unexpectedly stops, capping restart attempts with a
+// sliding-window `max_restarts` to guard against restart storms.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::service_ctrl::{ServiceController, ServiceState, TimeSource};
+use crate::{RestartPolicy, ServiceEntry};
+
+/// Sliding window over which restart attempts are counted against `max_restarts`, so
+/// restarts spread out over a long run don't trip the same cap as a genuine storm.
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+
+/// Outcome of supervising a single service until it settles, exhausts its restart
+/// budget, or the run is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisionOutcome {
+    /// Supervision was cancelled via `stop_flag`.
+    Cancelled,
+    /// The entry's restart policy is [`RestartPolicy::Never`]; nothing was watched.
+    NotSupervised,
+    /// The entry's policy is [`RestartPolicy::OnFailure`] and the service stopped
+    /// because a stop was requested (`requested_stop` was set), not because it
+    /// failed; supervision ends without restarting it.
+    StoppedAsRequested,
+    /// `max_restarts` attempts occurred within [`RESTART_WINDOW`]; the entry should be
+    /// marked failed.
+    RestartBudgetExceeded,
+}
+
+/// Watches `svc_name`'s state and restarts it per `entry`'s [`RestartPolicy`] whenever
+/// it is observed STOPPED, polling every `poll_interval` via `time`.
+///
+/// Returns [`SupervisionOutcome::NotSupervised`] immediately if the policy is
+/// [`RestartPolicy::Never`]. Otherwise loops until `stop_flag` is set (returning
+/// [`SupervisionOutcome::Cancelled`]) or the restart budget is exhausted (returning
+/// [`SupervisionOutcome::RestartBudgetExceeded`]).
+///
+/// `requested_stop` distinguishes a deliberate stop (e.g. the run loop stopping this
+/// service on purpose) from an unexpected crash. [`RestartPolicy::OnFailure`] only
+/// restarts on the latter: if the service is observed STOPPED while `requested_stop`
+/// is set, supervision ends with [`SupervisionOutcome::StoppedAsRequested`] instead of
+/// restarting. [`RestartPolicy::Always`] ignores `requested_stop` and restarts either
+/// way.
+pub fn supervise(
+    controller: &dyn ServiceController,
+    time: &dyn TimeSource,
+    entry: &ServiceEntry,
+    svc_name: &str,
+    stop_flag: &Arc<AtomicBool>,
+    requested_stop: &Arc<AtomicBool>,
+    poll_interval: Duration,
+) -> SupervisionOutcome {
+    if entry.restart_policy() == RestartPolicy::Never {
+        return SupervisionOutcome::NotSupervised;
+    }
+
+    let mut restart_times: Vec<Instant> = Vec::new();
+
+    loop {
+        if stop_flag.load(Ordering::SeqCst) {
+            return SupervisionOutcome::Cancelled;
+        }
+
+        let state = controller.query(svc_name).unwrap_or(ServiceState::Unknown);
+        if state != ServiceState::Stopped {
+            time.sleep(poll_interval);
+            continue;
+        }
+
+        if entry.restart_policy() == RestartPolicy::OnFailure && requested_stop.load(Ordering::SeqCst) {
+            return SupervisionOutcome::StoppedAsRequested;
+        }
+
+        let now = time.now();
+        restart_times.retain(|&t| now.duration_since(t) < RESTART_WINDOW);
+
+        if restart_times.len() as u32 >= entry.max_restarts() {
+            return SupervisionOutcome::RestartBudgetExceeded;
+        }
+
+        time.sleep(entry.restart_delay());
+        let _ = controller.start(svc_name);
+        restart_times.push(time.now());
+    }
+}
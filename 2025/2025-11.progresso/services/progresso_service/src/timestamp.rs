@@ -0,0 +1,84 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Typed Timestamps
+//
+// Lifecycle timestamps were previously raw `Option<String>` fields filled with
+// `Local::now().to_rfc3339()`, so nothing validated that a loaded XML file actually
+// contained a well-formed RFC 3339 value, and nothing could do duration math on them.
+//
+// `Timestamp` is a compile-time-selected alias, mirroring how bollard supports dual
+// `chrono`/`time` backends: with the default `chrono` feature it is
+// `chrono::DateTime<chrono::Local>`; with the `time` feature it is
+// `time::OffsetDateTime`. Either way, the on-disk XML format is unchanged -- the
+// `serde_rfc3339` module (de)serializes to/from an RFC 3339 string -- and malformed
+// timestamps are rejected with a clear error at `parse_ordem` time.
+
+#[cfg(all(feature = "chrono", feature = "time"))]
+compile_error!("the `chrono` and `time` Cargo features are mutually exclusive; enable only one");
+
+/// A lifecycle timestamp. See the module docs for the two backends this can be.
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::Local>;
+
+/// A lifecycle timestamp. See the module docs for the two backends this can be.
+#[cfg(feature = "time")]
+pub type Timestamp = time::OffsetDateTime;
+
+/// Returns the current time as a [`Timestamp`].
+#[cfg(feature = "chrono")]
+pub fn now() -> Timestamp {
+    chrono::Local::now()
+}
+
+/// Returns the current time as a [`Timestamp`].
+#[cfg(feature = "time")]
+pub fn now() -> Timestamp {
+    time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc())
+}
+
+/// Serializes/deserializes an `Option<Timestamp>` as an RFC 3339 string, so the XML
+/// format is unchanged regardless of which backend feature is selected.
+pub mod serde_rfc3339 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::Timestamp;
+
+    pub fn serialize<S: Serializer>(value: &Option<Timestamp>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(ts) => serializer.serialize_str(&format_rfc3339(ts)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Timestamp>, D::Error> {
+        let raw: Option<String> = Option::deserialize(deserializer)?;
+        match raw {
+            Some(s) => parse_rfc3339(&s).map(Some).map_err(serde::de::Error::custom),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    fn format_rfc3339(ts: &Timestamp) -> String {
+        ts.to_rfc3339()
+    }
+
+    #[cfg(feature = "chrono")]
+    fn parse_rfc3339(s: &str) -> Result<Timestamp, String> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&chrono::Local))
+            .map_err(|e| format!("invalid RFC 3339 timestamp {s:?}: {e}"))
+    }
+
+    #[cfg(feature = "time")]
+    fn format_rfc3339(ts: &Timestamp) -> String {
+        ts.format(&time::format_description::well_known::Rfc3339)
+            .expect("OffsetDateTime always formats as RFC 3339")
+    }
+
+    #[cfg(feature = "time")]
+    fn parse_rfc3339(s: &str) -> Result<Timestamp, String> {
+        time::OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339)
+            .map_err(|e| format!("invalid RFC 3339 timestamp {s:?}: {e}"))
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Elapsed-Duration Profiling
+//
+// The lifecycle timestamps say *when* an event happened, but not *how long* each phase
+// took, which is what operators actually want for performance diagnosis. Borrowing the
+// nested-timing idea from rustc's `TIME_DEPTH` utility, `PhaseTimer` measures wall-clock
+// durations (via `Instant`, not the pluggable [`crate::timestamp::Timestamp`], since
+// these are relative elapsed times rather than points in time) from a fixed run start
+// and stamps each phase's elapsed milliseconds onto a `ServiceEntry` as it completes.
+
+use std::time::Instant;
+
+use crate::{OrdemTargets, ServiceEntry};
+
+/// Measures wall-clock durations from a fixed start instant, stamping each phase's
+/// elapsed time (in milliseconds) onto a `ServiceEntry` as it completes.
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseTimer {
+    start: Instant,
+}
+
+impl PhaseTimer {
+    /// Starts a new timer anchored at the current instant.
+    pub fn start() -> Self {
+        Self { start: Instant::now() }
+    }
+
+    /// Records the elapsed time since `start()` as the "time to start processing" phase.
+    pub fn record_start_processing(&self, entry: &mut ServiceEntry) {
+        entry.start_processing_duration_ms = Some(self.elapsed_ms());
+    }
+
+    /// Records the elapsed time since `start()` as the "time until stop issued" phase.
+    pub fn record_stop(&self, entry: &mut ServiceEntry) {
+        entry.stop_duration_ms = Some(self.elapsed_ms());
+    }
+
+    /// Records the elapsed time since `start()` as the "time until CPU became
+    /// responsive" phase.
+    pub fn record_cpu_responsive(&self, entry: &mut ServiceEntry) {
+        entry.cpu_responsive_duration_ms = Some(self.elapsed_ms());
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+/// Prints an indented per-service breakdown of recorded phase durations, highlighting
+/// the slowest phase, so operators can spot services that are slow to settle.
+///
+/// Services with no recorded durations are skipped.
+pub fn print_duration_summary(ordem: &OrdemTargets) {
+    for entry in &ordem.services {
+        let Some(name) = entry.name() else { continue };
+        let phases = [
+            ("start processing", entry.start_processing_duration_ms),
+            ("stop issued", entry.stop_duration_ms),
+            ("cpu responsive", entry.cpu_responsive_duration_ms),
+        ];
+
+        if phases.iter().all(|(_, ms)| ms.is_none()) {
+            continue;
+        }
+
+        let slowest = phases.iter().filter_map(|(_, ms)| *ms).max();
+        println!("{name}:");
+        for (phase, ms) in phases {
+            let Some(ms) = ms else { continue };
+            let marker = if Some(ms) == slowest { "  <- slowest" } else { "" };
+            println!("  {phase}: {ms}ms{marker}");
+        }
+    }
+}
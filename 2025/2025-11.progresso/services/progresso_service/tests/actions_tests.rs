@@ -0,0 +1,76 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Action Plugin Registry Tests
+//
+// Exercises `ActionRegistry` dispatch, including the default `"start"`/`"stop"`
+// plugins and custom plugins registered by callers.
+
+use progresso_service::actions::{self, ActionRegistry};
+use progresso_service::ServiceEntry;
+
+fn entry(name: &str, end_mode: &str) -> ServiceEntry {
+    ServiceEntry {
+        name: Some(name.to_string()),
+        end_mode: Some(end_mode.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn dispatching_start_records_timestamps() {
+    let registry = actions::create_registry();
+    let mut svc = entry("Alpha", "Automatic");
+
+    registry.dispatch("start", &mut svc).expect("start should be registered");
+
+    assert!(svc.start_processing_time.is_some());
+    assert!(svc.end_time.is_some());
+}
+
+#[test]
+fn dispatching_stop_records_stop_time() {
+    let registry = actions::create_registry();
+    let mut svc = entry("Beta", "Manual");
+
+    registry.dispatch("stop", &mut svc).expect("stop should be registered");
+
+    assert!(svc.stop_time.is_some());
+}
+
+#[test]
+fn action_for_derives_start_or_stop_from_end_mode() {
+    assert_eq!(actions::action_for(&entry("Alpha", "Automatic")), "start");
+    assert_eq!(actions::action_for(&entry("Beta", "Manual")), "stop");
+}
+
+#[test]
+fn dispatching_an_unregistered_action_is_an_error() {
+    let registry = ActionRegistry::new();
+    let mut svc = entry("Gamma", "Automatic");
+
+    let err = registry.dispatch("reboot", &mut svc).expect_err("no plugin registered");
+    assert!(err.to_string().contains("reboot"));
+}
+
+#[test]
+fn custom_plugins_can_be_registered_and_dispatched() {
+    let mut registry = ActionRegistry::new();
+    registry.add_plugin("rename", |entry| {
+        entry.name = Some(format!("{}-renamed", entry.name().unwrap_or_default()));
+        Ok(())
+    });
+
+    let mut svc = entry("Delta", "Automatic");
+    registry.dispatch("rename", &mut svc).expect("rename should be registered");
+
+    assert_eq!(svc.name.as_deref(), Some("Delta-renamed"));
+}
+
+#[test]
+fn has_plugin_reflects_registered_names() {
+    let registry = actions::create_registry();
+
+    assert!(registry.has_plugin("start"));
+    assert!(registry.has_plugin("create"));
+    assert!(!registry.has_plugin("reboot"));
+}
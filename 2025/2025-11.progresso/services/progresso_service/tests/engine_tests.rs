@@ -0,0 +1,92 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Orchestration Engine Tests
+//
+// Exercises dependency layering, cycle detection, and the end-to-end concurrent run
+// against a `MockController` so no real `sc.exe` or subprocess is involved.
+
+use std::sync::{atomic::AtomicBool, Arc};
+
+use progresso_service::engine::{self, ServiceOutcome};
+use progresso_service::service_ctrl::{MockController, ServiceState};
+use progresso_service::{OrdemTargets, ServiceEntry};
+
+fn entry(name: &str, depends_on: &[&str]) -> ServiceEntry {
+    ServiceEntry {
+        name: Some(name.to_string()),
+        end_mode: Some("Automatic".to_string()),
+        depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn layered_order_respects_dependencies() {
+    let entries = vec![entry("A", &[]), entry("B", &["A"]), entry("C", &["A", "B"])];
+    let layers = engine::layered_order(&entries).expect("no cycle");
+
+    assert_eq!(layers, vec![vec!["A".to_string()], vec!["B".to_string()], vec!["C".to_string()]]);
+}
+
+#[test]
+fn layered_order_groups_independent_services() {
+    let entries = vec![entry("A", &[]), entry("B", &[]), entry("C", &["A", "B"])];
+    let layers = engine::layered_order(&entries).expect("no cycle");
+
+    assert_eq!(layers.len(), 2, "A and B should share a layer, C its own");
+    assert_eq!(layers[0], vec!["A".to_string(), "B".to_string()]);
+    assert_eq!(layers[1], vec!["C".to_string()]);
+}
+
+#[test]
+fn layered_order_rejects_cycles() {
+    let entries = vec![entry("A", &["B"]), entry("B", &["A"])];
+    let err = engine::layered_order(&entries).expect_err("should detect cycle");
+
+    let mut services = err.services;
+    services.sort();
+    assert_eq!(services, vec!["A".to_string(), "B".to_string()]);
+}
+
+#[test]
+fn layered_order_ignores_unknown_dependency_names() {
+    let entries = vec![entry("A", &["GhostService"])];
+    let layers = engine::layered_order(&entries).expect("unknown deps should be ignored");
+
+    assert_eq!(layers, vec![vec!["A".to_string()]]);
+}
+
+#[test]
+fn run_starts_dependents_after_dependencies_succeed() {
+    let controller = MockController::new();
+    controller.script("A", [ServiceState::Running]);
+    controller.script("B", [ServiceState::Running]);
+
+    let mut ordem = OrdemTargets {
+        services: vec![entry("A", &[]), entry("B", &["A"])],
+    };
+
+    let report = engine::run(&controller, &mut ordem, Arc::new(AtomicBool::new(false)), 2)
+        .expect("no cycle");
+
+    assert_eq!(report.outcomes.get("A"), Some(&ServiceOutcome::Started));
+    assert_eq!(report.outcomes.get("B"), Some(&ServiceOutcome::Started));
+    assert_eq!(ordem.services.len(), 2, "all entries should be restored after the run");
+}
+
+#[test]
+fn run_skips_dependents_of_a_timed_out_service() {
+    let controller = MockController::new();
+    controller.script("A", [ServiceState::Stopped]); // never reaches RUNNING
+    controller.script("B", [ServiceState::Running]);
+
+    let mut ordem = OrdemTargets {
+        services: vec![entry("A", &[]), entry("B", &["A"])],
+    };
+
+    let report = engine::run(&controller, &mut ordem, Arc::new(AtomicBool::new(false)), 1)
+        .expect("no cycle");
+
+    assert_eq!(report.outcomes.get("A"), Some(&ServiceOutcome::TimedOut));
+    assert_eq!(report.outcomes.get("B"), Some(&ServiceOutcome::SkippedDependencyFailed));
+}
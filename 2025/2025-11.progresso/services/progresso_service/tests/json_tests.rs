@@ -0,0 +1,49 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// JSON Progress Serialization Tests
+//
+// Exercises `write_progress_json`/`parse_ordem_json`, gated behind the `json`
+// Cargo feature, alongside the existing XML path.
+
+#![cfg(feature = "json")]
+
+use progresso_service::{
+    parse_ordem_json, populate_test_timestamps, write_progress_json, OrdemTargets, ServiceEntry,
+};
+
+#[test]
+fn json_round_trips_service_fields() {
+    let mut ordem = OrdemTargets {
+        services: vec![ServiceEntry {
+            name: Some("TestSvc".to_string()),
+            end_mode: Some("Automatic".to_string()),
+            ..Default::default()
+        }],
+    };
+    populate_test_timestamps(&mut ordem);
+
+    let json = write_progress_json(&ordem).expect("serialize failed");
+    let parsed = parse_ordem_json(&json).expect("parse failed");
+
+    assert_eq!(parsed.services.len(), 1);
+    assert_eq!(parsed.services[0].name.as_deref(), Some("TestSvc"));
+    assert_eq!(parsed.services[0].start_processing_time, ordem.services[0].start_processing_time);
+}
+
+#[test]
+fn unset_optional_fields_are_omitted_not_null() {
+    let ordem = OrdemTargets { services: vec![ServiceEntry::default()] };
+
+    let json = write_progress_json(&ordem).expect("serialize failed");
+    assert!(!json.contains("null"), "unset fields should be omitted, not emitted as null: {json}");
+}
+
+#[test]
+fn empty_ordem_round_trips() {
+    let ordem = OrdemTargets::default();
+
+    let json = write_progress_json(&ordem).expect("serialize failed");
+    let parsed = parse_ordem_json(&json).expect("parse failed");
+
+    assert!(parsed.is_empty());
+}
@@ -2,160 +2,200 @@
 //
 // Mock Service Control Tests
 //
-// Tests service_ctrl module functionality using a mock `sc` command that simulates
-// Windows service query responses. This allows testing on any platform without
-// requiring actual Windows services.
-
-use std::env;
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+// Tests service_ctrl module functionality using the in-memory `MockController`, which
+// scripts service state transitions without spawning any subprocess. This replaces the
+// previous approach of prepending a directory to the process-wide `PATH` and writing
+// shell/batch scripts, which was racy and not safe to run in parallel.
+
 use std::sync::{Arc, atomic::AtomicBool};
+use std::time::Duration;
+
+use progresso_service::service_ctrl::{
+    is_service_running_with, wait_for_adaptive, wait_for_state, wait_for_state_with,
+    MockController, MockTimeSource, ServiceState, ServiceStatusDetail,
+};
+
+/// Tests `is_service_running_with()` against a scripted running and a scripted
+/// stopped service.
+#[test]
+fn test_is_service_running() {
+    let controller = MockController::new();
+    controller.script("RunningSvc", [ServiceState::Running]);
+    controller.script("OtherSvc", [ServiceState::Stopped]);
 
-use tempfile::TempDir;
-
-use progresso_service::service_ctrl;
-
-/// Creates a mock `sc` script in the given directory.
-///
-/// The mock script responds to `sc query <service>` commands:
-/// - "RunningSvc" returns RUNNING state
-/// - Any other service returns STOPPED state
-///
-/// # Arguments
-///
-/// * `dir` - Temporary directory to create the script in
-///
-/// # Returns
-///
-/// Path to the created script
-fn create_mock_sc_script(dir: &TempDir) -> PathBuf {
-    let script_path = if cfg!(windows) {
-        let path = dir.path().join("sc.bat");
-        let mut file = File::create(&path).expect("create sc.bat");
-
-        // Windows batch script that mocks sc.exe query command
-        writeln!(file, "@echo off").unwrap();
-        writeln!(file, r#"if "%1"=="query" ("#).unwrap();
-        writeln!(file, r#"  if "%2"=="RunningSvc" ("#).unwrap();
-        writeln!(file, "    echo SERVICE_NAME: %2").unwrap();
-        writeln!(file, "    echo         STATE              : 4  RUNNING").unwrap();
-        writeln!(file, "    exit /b 0").unwrap();
-        writeln!(file, "  ) else (").unwrap();
-        writeln!(file, "    echo SERVICE_NAME: %2").unwrap();
-        writeln!(file, "    echo         STATE              : 1  STOPPED").unwrap();
-        writeln!(file, "    exit /b 0").unwrap();
-        writeln!(file, "  )").unwrap();
-        writeln!(file, ")").unwrap();
-        writeln!(file, "exit /b 0").unwrap();
-
-        path
-    } else {
-        let path = dir.path().join("sc");
-        let mut file = File::create(&path).expect("create sc");
-
-        // Unix shell script that mocks sc command
-        writeln!(file, "#!/bin/sh").unwrap();
-        writeln!(file, r#"if [ "$1" = "query" ]; then"#).unwrap();
-        writeln!(file, r#"  if [ "$2" = "RunningSvc" ]; then"#).unwrap();
-        writeln!(file, "    echo SERVICE_NAME: $2").unwrap();
-        writeln!(file, "    echo '        STATE              : 4  RUNNING'").unwrap();
-        writeln!(file, "    exit 0").unwrap();
-        writeln!(file, "  else").unwrap();
-        writeln!(file, "    echo SERVICE_NAME: $2").unwrap();
-        writeln!(file, "    echo '        STATE              : 1  STOPPED'").unwrap();
-        writeln!(file, "    exit 0").unwrap();
-        writeln!(file, "  fi").unwrap();
-        writeln!(file, "fi").unwrap();
-        writeln!(file, "exit 0").unwrap();
-
-        // Make script executable on Unix
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
-        }
-
-        path
-    };
-
-    script_path
+    assert!(
+        is_service_running_with(&controller, "RunningSvc"),
+        "RunningSvc should be detected as running"
+    );
+    assert!(
+        !is_service_running_with(&controller, "OtherSvc"),
+        "OtherSvc should be detected as stopped"
+    );
 }
 
-/// Prepends a directory to the PATH environment variable.
-///
-/// # Returns
-///
-/// The original PATH value (for restoration)
-fn prepend_to_path(dir: &TempDir) -> std::ffi::OsString {
-    let original_path = env::var_os("PATH").unwrap_or_default();
+/// Tests `wait_for_state_with()` succeeds immediately for an already-running service.
+#[test]
+fn test_wait_succeeds_for_running_service() {
+    let controller = MockController::new();
+    controller.script("RunningSvc", [ServiceState::Running]);
 
-    let mut paths = vec![dir.path().to_path_buf()];
-    paths.extend(env::split_paths(&original_path));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let result = wait_for_state_with(&controller, "RunningSvc", "RUNNING", 2, Arc::clone(&stop_flag));
+    assert!(result, "Should detect RunningSvc as RUNNING");
+}
 
-    let new_path = env::join_paths(paths).expect("join paths");
-    env::set_var("PATH", &new_path);
+/// Tests `wait_for_state_with()` times out for a service that never reaches the
+/// desired state. It uses the real clock, so it takes about the 2s timeout in
+/// wall-clock time; the `MockTimeSource` tests below cover the instant variant.
+#[test]
+fn test_wait_times_out_for_stopped_service() {
+    let controller = MockController::new();
+    controller.script("OtherSvc", [ServiceState::Stopped]);
 
-    original_path
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let result = wait_for_state_with(&controller, "OtherSvc", "RUNNING", 2, Arc::clone(&stop_flag));
+    assert!(!result, "Should timeout waiting for OtherSvc RUNNING");
 }
 
-/// Tests service_ctrl functions using a mock sc command.
-///
-/// This test:
-/// 1. Creates a mock `sc` script in a temp directory
-/// 2. Prepends that directory to PATH so the mock is found first
-/// 3. Tests is_service_running() with running and stopped services
-/// 4. Tests wait_for_service_state_with_stop() with various scenarios
-/// 5. Restores the original PATH
+/// Tests that a pre-set stop flag short-circuits the wait before any polling occurs.
 #[test]
-fn test_is_service_running_and_wait() {
-    // Setup: create mock sc and modify PATH
-    let temp_dir = TempDir::new().expect("create temp directory");
-    let _script_path = create_mock_sc_script(&temp_dir);
-    let original_path = prepend_to_path(&temp_dir);
+fn test_wait_honors_stop_flag() {
+    let controller = MockController::new();
+    controller.script("RunningSvc", [ServiceState::Stopped]);
 
-    // Test 1: is_service_running() detects running service
-    assert!(
-        service_ctrl::is_service_running("RunningSvc"),
-        "RunningSvc should be detected as running"
+    let stop_flag = Arc::new(AtomicBool::new(true)); // Pre-set to cancelled
+    let result = wait_for_state_with(&controller, "RunningSvc", "RUNNING", 10, Arc::clone(&stop_flag));
+    assert!(!result, "Should return false immediately when stop flag is set");
+}
+
+/// Tests that `wait_for_state_with()` observes a scripted sequence of transitions
+/// (e.g. STOPPED -> STOPPED -> RUNNING) before succeeding.
+#[test]
+fn test_wait_observes_scripted_transitions() {
+    let controller = MockController::new();
+    controller.script(
+        "SlowSvc",
+        [ServiceState::Stopped, ServiceState::Stopped, ServiceState::Running],
     );
 
-    // Test 2: is_service_running() detects stopped service
-    assert!(
-        !service_ctrl::is_service_running("OtherSvc"),
-        "OtherSvc should be detected as stopped"
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let result = wait_for_state_with(&controller, "SlowSvc", "RUNNING", 5, Arc::clone(&stop_flag));
+    assert!(result, "Should observe the scripted transition to RUNNING");
+}
+
+/// Tests that `wait_for_state()` with a `MockTimeSource` times out deterministically
+/// after polling a controlled number of times, with no real wall-clock delay.
+#[test]
+fn test_wait_times_out_deterministically_with_mock_clock() {
+    let controller = MockController::new();
+    controller.script("OtherSvc", [ServiceState::Stopped]);
+    let time = MockTimeSource::new();
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let result = wait_for_state(&controller, &time, "OtherSvc", "RUNNING", 5, Arc::clone(&stop_flag));
+
+    assert!(!result, "Should give up once virtual time exceeds the timeout");
+    assert_eq!(time.sleep_count(), 5, "Should poll once per virtual second before timing out");
+}
+
+/// Tests that the stop-flag short-circuit is checked before the first sleep, so a
+/// cancelled wait never advances virtual time.
+#[test]
+fn test_wait_checks_stop_flag_before_sleeping() {
+    let controller = MockController::new();
+    controller.script("RunningSvc", [ServiceState::Stopped]);
+    let time = MockTimeSource::new();
+
+    let stop_flag = Arc::new(AtomicBool::new(true));
+    let result = wait_for_state(&controller, &time, "RunningSvc", "RUNNING", 10, Arc::clone(&stop_flag));
+
+    assert!(!result);
+    assert_eq!(time.sleep_count(), 0, "Should return before ever sleeping");
+}
+
+/// Tests that `wait_for_adaptive()` succeeds for a service scripted via the plain
+/// `script()` (no detail scripted), exercising `query_detailed`'s default fallback.
+#[test]
+fn test_wait_adaptive_succeeds_via_default_detail_fallback() {
+    let controller = MockController::new();
+    controller.script("RunningSvc", [ServiceState::Running]);
+    let time = MockTimeSource::new();
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let result =
+        wait_for_adaptive(&controller, &time, "RunningSvc", "RUNNING", 60, Duration::from_secs(30), stop_flag);
+    assert!(result, "Should detect RunningSvc as RUNNING via the default query_detailed fallback");
+}
+
+/// Tests that an advancing `dwCheckPoint` keeps resetting the deadline, letting the
+/// wait survive well past `wait_hint_floor` before the service finally reaches the
+/// desired state.
+#[test]
+fn test_wait_adaptive_resets_deadline_on_checkpoint_advance() {
+    let controller = MockController::new();
+    controller.script_detailed(
+        "SlowSvc",
+        [
+            ServiceStatusDetail { state: ServiceState::Stopped, checkpoint: 1, wait_hint: Duration::from_secs(2) },
+            ServiceStatusDetail { state: ServiceState::Stopped, checkpoint: 2, wait_hint: Duration::from_secs(2) },
+            ServiceStatusDetail { state: ServiceState::Running, checkpoint: 2, wait_hint: Duration::from_secs(2) },
+        ],
     );
+    let time = MockTimeSource::new();
 
-    // Test 3: wait_for_service_state_with_stop() succeeds for running service
     let stop_flag = Arc::new(AtomicBool::new(false));
-    let result = service_ctrl::wait_for_service_state_with_stop(
-        "RunningSvc",
-        "RUNNING",
-        2,
-        Arc::clone(&stop_flag),
+    let result =
+        wait_for_adaptive(&controller, &time, "SlowSvc", "RUNNING", 60, Duration::from_secs(1), stop_flag);
+
+    assert!(result, "Should succeed once the scripted checkpoints reach RUNNING");
+}
+
+/// Tests that a stalled `dwCheckPoint` (never advancing) gives up once
+/// `wait_hint_floor` elapses since the last advance, without waiting for the
+/// overall `timeout_secs` ceiling.
+#[test]
+fn test_wait_adaptive_gives_up_when_checkpoint_stalls() {
+    let controller = MockController::new();
+    controller.script_detailed(
+        "StuckSvc",
+        [ServiceStatusDetail { state: ServiceState::Stopped, checkpoint: 1, wait_hint: Duration::from_secs(1) }],
     );
-    assert!(result, "Should detect RunningSvc as RUNNING");
+    let time = MockTimeSource::new();
 
-    // Test 4: wait_for_service_state_with_stop() times out for stopped service
     let stop_flag = Arc::new(AtomicBool::new(false));
-    let result = service_ctrl::wait_for_service_state_with_stop(
-        "OtherSvc",
-        "RUNNING",
-        2,
-        Arc::clone(&stop_flag),
+    let result =
+        wait_for_adaptive(&controller, &time, "StuckSvc", "RUNNING", 60, Duration::from_secs(2), stop_flag);
+
+    assert!(!result, "Should give up once wait_hint_floor elapses with no further checkpoint advance");
+    assert_eq!(time.sleep_count(), 2, "Should sleep twice (after the polls at 0s and 1s) before the 2s floor deadline passes");
+}
+
+/// Tests that `timeout_secs` is enforced as an absolute ceiling even while the
+/// service keeps reporting checkpoint progress indefinitely.
+#[test]
+fn test_wait_adaptive_respects_absolute_ceiling() {
+    let controller = MockController::new();
+    controller.script_detailed(
+        "ForeverProgressingSvc",
+        (1..=10).map(|checkpoint| ServiceStatusDetail {
+            state: ServiceState::Stopped,
+            checkpoint,
+            wait_hint: Duration::from_secs(1),
+        }),
     );
-    assert!(!result, "Should timeout waiting for OtherSvc RUNNING");
+    let time = MockTimeSource::new();
 
-    // Test 5: Early cancellation via stop flag
-    let stop_flag = Arc::new(AtomicBool::new(true)); // Pre-set to cancelled
-    let result = service_ctrl::wait_for_service_state_with_stop(
-        "RunningSvc",
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let result = wait_for_adaptive(
+        &controller,
+        &time,
+        "ForeverProgressingSvc",
         "RUNNING",
-        10,
-        Arc::clone(&stop_flag),
+        3,
+        Duration::from_secs(1),
+        stop_flag,
     );
-    assert!(!result, "Should return false immediately when stop flag is set");
 
-    // Cleanup: restore original PATH
-    env::set_var("PATH", &original_path);
+    assert!(!result, "The absolute ceiling should cut off the wait despite continual checkpoint progress");
+    assert_eq!(time.sleep_count(), 3, "Should poll three times before the 3s ceiling is reached");
 }
@@ -0,0 +1,84 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Async Orchestrator Tests
+//
+// Exercises `ServiceOrchestrator` against a `MockController` under a paused tokio
+// clock, so retry/backoff/timeout loops complete instantly in wall-clock time.
+
+use std::time::Duration;
+
+use progresso_service::orchestrator::{ServiceOrchestrator, ServiceResult};
+use progresso_service::service_ctrl::{MockController, ServiceState};
+use progresso_service::{OrdemTargets, ServiceEntry};
+
+fn entry(name: &str, end_mode: &str) -> ServiceEntry {
+    ServiceEntry {
+        name: Some(name.to_string()),
+        end_mode: Some(end_mode.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn run_records_success_and_end_time_for_a_started_service() {
+    let controller = MockController::new();
+    controller.script("Alpha", [ServiceState::Running]);
+
+    let mut ordem = OrdemTargets { services: vec![entry("Alpha", "Automatic")] };
+    let orchestrator = ServiceOrchestrator::new(&controller, Duration::from_secs(5), 2);
+
+    let results = orchestrator.run(&mut ordem).await;
+
+    assert_eq!(results, vec![("Alpha".to_string(), ServiceResult::Succeeded)]);
+    assert!(ordem.services[0].start_processing_time.is_some());
+    assert!(ordem.services[0].end_time.is_some());
+}
+
+#[tokio::test(start_paused = true)]
+async fn run_records_stop_time_for_a_service_targeted_to_stop() {
+    let controller = MockController::new();
+    controller.script("Beta", [ServiceState::Stopped]);
+
+    let mut ordem = OrdemTargets { services: vec![entry("Beta", "Manual")] };
+    let orchestrator = ServiceOrchestrator::new(&controller, Duration::from_secs(5), 2);
+
+    let results = orchestrator.run(&mut ordem).await;
+
+    assert_eq!(results, vec![("Beta".to_string(), ServiceResult::Succeeded)]);
+    assert!(ordem.services[0].stop_time.is_some());
+}
+
+#[tokio::test(start_paused = true)]
+async fn run_reports_timed_out_after_exhausting_retries() {
+    let controller = MockController::new();
+    controller.script("Gamma", [ServiceState::Stopped]); // never reaches RUNNING
+
+    let mut ordem = OrdemTargets { services: vec![entry("Gamma", "Automatic")] };
+    let orchestrator = ServiceOrchestrator::new(&controller, Duration::from_millis(10), 2);
+
+    let results = orchestrator.run(&mut ordem).await;
+
+    assert_eq!(results, vec![("Gamma".to_string(), ServiceResult::TimedOut)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn run_processes_multiple_services_in_order() {
+    let controller = MockController::new();
+    controller.script("Alpha", [ServiceState::Running]);
+    controller.script("Beta", [ServiceState::Running]);
+
+    let mut ordem = OrdemTargets {
+        services: vec![entry("Alpha", "Automatic"), entry("Beta", "Automatic")],
+    };
+    let orchestrator = ServiceOrchestrator::new(&controller, Duration::from_secs(5), 1);
+
+    let results = orchestrator.run(&mut ordem).await;
+
+    assert_eq!(
+        results,
+        vec![
+            ("Alpha".to_string(), ServiceResult::Succeeded),
+            ("Beta".to_string(), ServiceResult::Succeeded),
+        ]
+    );
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Processing Order Tests
+//
+// Exercises `OrdemTargets::processing_order()`/`stop_order()`, the Kahn
+// topological-sort view over `depends_on` edges.
+
+use progresso_service::{OrdemTargets, ServiceEntry};
+
+fn entry(name: &str, depends_on: &[&str]) -> ServiceEntry {
+    ServiceEntry {
+        name: Some(name.to_string()),
+        depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn processing_order_respects_dependencies() {
+    let ordem = OrdemTargets {
+        services: vec![entry("C", &["A", "B"]), entry("A", &[]), entry("B", &["A"])],
+    };
+
+    let order = ordem.processing_order().expect("no cycle");
+
+    assert_eq!(order, vec!["A".to_string(), "B".to_string(), "C".to_string()]);
+}
+
+#[test]
+fn stop_order_is_the_reverse_of_processing_order() {
+    let ordem = OrdemTargets {
+        services: vec![entry("A", &[]), entry("B", &["A"]), entry("C", &["B"])],
+    };
+
+    let processing = ordem.processing_order().expect("no cycle");
+    let stop = ordem.stop_order().expect("no cycle");
+
+    assert_eq!(stop, processing.into_iter().rev().collect::<Vec<_>>());
+    assert_eq!(stop, vec!["C".to_string(), "B".to_string(), "A".to_string()]);
+}
+
+#[test]
+fn processing_order_reports_a_cycle() {
+    let ordem = OrdemTargets {
+        services: vec![entry("A", &["B"]), entry("B", &["A"])],
+    };
+
+    let err = ordem.processing_order().expect_err("should detect cycle");
+
+    let mut services = err.services;
+    services.sort();
+    assert_eq!(services, vec!["A".to_string(), "B".to_string()]);
+}
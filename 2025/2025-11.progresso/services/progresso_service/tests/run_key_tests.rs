@@ -0,0 +1,47 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Run-Key Backend Tests
+//
+// On non-Windows platforms `register_run_key`/`unregister_run_key` are stubs, so these
+// tests cover the parts of the backend that are platform-independent: backend
+// selection on `ServiceEntry` and the stub's error behavior.
+
+use progresso_service::run_key::{register_run_key, unregister_run_key};
+use progresso_service::ServiceEntry;
+
+#[test]
+fn uses_run_key_backend_is_case_insensitive() {
+    let entry = ServiceEntry {
+        backend: Some("Run-Key".to_string()),
+        ..Default::default()
+    };
+    assert!(entry.uses_run_key_backend());
+}
+
+#[test]
+fn default_backend_is_not_run_key() {
+    let entry = ServiceEntry::default();
+    assert!(!entry.uses_run_key_backend());
+
+    let sc_entry = ServiceEntry {
+        backend: Some("service".to_string()),
+        ..Default::default()
+    };
+    assert!(!sc_entry.uses_run_key_backend());
+}
+
+#[test]
+#[cfg(not(windows))]
+fn non_windows_register_and_unregister_are_unsupported() {
+    let entry = ServiceEntry {
+        name: Some("Svc".to_string()),
+        path: Some("/usr/bin/svc".to_string()),
+        ..Default::default()
+    };
+
+    let register_err = register_run_key(&entry).unwrap_err();
+    assert_eq!(register_err.kind(), std::io::ErrorKind::Unsupported);
+
+    let unregister_err = unregister_run_key("Svc").unwrap_err();
+    assert_eq!(unregister_err.kind(), std::io::ErrorKind::Unsupported);
+}
@@ -0,0 +1,115 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Supervisor Tests
+//
+// Exercises the restart-policy supervisor against a `MockController`/`MockTimeSource`
+// pair so restart storms are capped deterministically with no real delay.
+
+use std::sync::{atomic::AtomicBool, Arc};
+use std::time::Duration;
+
+use progresso_service::service_ctrl::{MockController, MockTimeSource, ServiceState};
+use progresso_service::supervisor::{supervise, SupervisionOutcome};
+use progresso_service::{RestartPolicy, ServiceEntry};
+
+fn entry_with_policy(policy: RestartPolicy, max_restarts: u32) -> ServiceEntry {
+    ServiceEntry {
+        name: Some("Svc".to_string()),
+        restart: Some(policy),
+        restart_delay_secs: Some(1),
+        max_restarts: Some(max_restarts),
+        ..Default::default()
+    }
+}
+
+/// A `never` policy should not watch the service at all.
+#[test]
+fn never_policy_is_not_supervised() {
+    let controller = MockController::new();
+    let time = MockTimeSource::new();
+    let entry = entry_with_policy(RestartPolicy::Never, 3);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    let requested_stop = Arc::new(AtomicBool::new(false));
+    let outcome =
+        supervise(&controller, &time, &entry, "Svc", &stop_flag, &requested_stop, Duration::from_secs(1));
+    assert_eq!(outcome, SupervisionOutcome::NotSupervised);
+}
+
+/// An `on-failure` policy should restart a stopped service until the restart budget
+/// is exhausted within the sliding window.
+#[test]
+fn on_failure_policy_caps_restart_storm() {
+    let controller = MockController::new();
+    controller.script("Svc", [ServiceState::Stopped]);
+    let time = MockTimeSource::new();
+    let entry = entry_with_policy(RestartPolicy::OnFailure, 2);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let requested_stop = Arc::new(AtomicBool::new(false));
+
+    let outcome =
+        supervise(&controller, &time, &entry, "Svc", &stop_flag, &requested_stop, Duration::from_secs(1));
+    assert_eq!(outcome, SupervisionOutcome::RestartBudgetExceeded);
+}
+
+/// An `always` policy should also be capped by `max_restarts`, not restart forever.
+#[test]
+fn always_policy_caps_restart_storm() {
+    let controller = MockController::new();
+    controller.script("Svc", [ServiceState::Stopped]);
+    let time = MockTimeSource::new();
+    let entry = entry_with_policy(RestartPolicy::Always, 1);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let requested_stop = Arc::new(AtomicBool::new(false));
+
+    let outcome =
+        supervise(&controller, &time, &entry, "Svc", &stop_flag, &requested_stop, Duration::from_secs(1));
+    assert_eq!(outcome, SupervisionOutcome::RestartBudgetExceeded);
+}
+
+/// An `on-failure` policy should NOT restart a service that stopped because a stop
+/// was deliberately requested, unlike `always` which restarts regardless.
+#[test]
+fn on_failure_policy_does_not_restart_a_requested_stop() {
+    let controller = MockController::new();
+    controller.script("Svc", [ServiceState::Stopped]);
+    let time = MockTimeSource::new();
+    let entry = entry_with_policy(RestartPolicy::OnFailure, 5);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let requested_stop = Arc::new(AtomicBool::new(true));
+
+    let outcome =
+        supervise(&controller, &time, &entry, "Svc", &stop_flag, &requested_stop, Duration::from_secs(1));
+    assert_eq!(outcome, SupervisionOutcome::StoppedAsRequested);
+}
+
+/// An `always` policy ignores `requested_stop` and keeps restarting a deliberately
+/// stopped service until its restart budget is exhausted.
+#[test]
+fn always_policy_restarts_even_a_requested_stop() {
+    let controller = MockController::new();
+    controller.script("Svc", [ServiceState::Stopped]);
+    let time = MockTimeSource::new();
+    let entry = entry_with_policy(RestartPolicy::Always, 1);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let requested_stop = Arc::new(AtomicBool::new(true));
+
+    let outcome =
+        supervise(&controller, &time, &entry, "Svc", &stop_flag, &requested_stop, Duration::from_secs(1));
+    assert_eq!(outcome, SupervisionOutcome::RestartBudgetExceeded);
+}
+
+/// Supervision should stop as soon as the stop flag is set, even mid-storm.
+#[test]
+fn stop_flag_cancels_supervision() {
+    let controller = MockController::new();
+    controller.script("Svc", [ServiceState::Stopped]);
+    let time = MockTimeSource::new();
+    let entry = entry_with_policy(RestartPolicy::Always, 1000);
+    let stop_flag = Arc::new(AtomicBool::new(true));
+    let requested_stop = Arc::new(AtomicBool::new(false));
+
+    let outcome =
+        supervise(&controller, &time, &entry, "Svc", &stop_flag, &requested_stop, Duration::from_secs(1));
+    assert_eq!(outcome, SupervisionOutcome::Cancelled);
+}
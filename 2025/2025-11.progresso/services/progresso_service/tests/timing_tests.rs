@@ -0,0 +1,51 @@
+// Copyright (c) 2025 - Alisson Sol
+//
+// Elapsed-Duration Profiling Tests
+//
+// Exercises `PhaseTimer` stamping `*_duration_ms` fields and their XML
+// `skip_serializing_if` behavior.
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use progresso_service::timing::PhaseTimer;
+use progresso_service::{write_progress_xml, OrdemTargets, ServiceEntry};
+
+#[test]
+fn phase_timer_stamps_increasing_elapsed_milliseconds() {
+    let timer = PhaseTimer::start();
+    let mut svc = ServiceEntry { name: Some("Alpha".to_string()), ..Default::default() };
+
+    timer.record_start_processing(&mut svc);
+    sleep(Duration::from_millis(5));
+    timer.record_stop(&mut svc);
+
+    let start = svc.start_processing_duration_ms.expect("recorded");
+    let stop = svc.stop_duration_ms.expect("recorded");
+    assert!(stop >= start, "later phases should have a larger or equal elapsed time");
+    assert!(svc.cpu_responsive_duration_ms.is_none(), "unrecorded phase stays unset");
+}
+
+#[test]
+fn unset_duration_fields_are_omitted_from_xml() {
+    let ordem = OrdemTargets {
+        services: vec![ServiceEntry { name: Some("Beta".to_string()), ..Default::default() }],
+    };
+
+    let output = write_progress_xml(&ordem).expect("serialize failed");
+    assert!(!output.contains("duration_ms"), "unset duration fields should not be emitted");
+}
+
+#[test]
+fn set_duration_fields_round_trip_through_xml() {
+    let ordem = OrdemTargets {
+        services: vec![ServiceEntry {
+            name: Some("Gamma".to_string()),
+            start_processing_duration_ms: Some(42),
+            ..Default::default()
+        }],
+    };
+
+    let output = write_progress_xml(&ordem).expect("serialize failed");
+    assert!(output.contains("<start_processing_duration_ms>42</start_processing_duration_ms>"));
+}
@@ -5,7 +5,10 @@
 // Tests for verifying XML round-trip (parse -> modify -> serialize) functionality
 // and edge case handling for the ordem configuration format.
 
-use progresso_service::{parse_ordem, populate_test_timestamps, write_progress_xml, OrdemTargets, ServiceEntry};
+use progresso_service::{
+    parse_ordem, populate_test_timestamps, write_progress_xml, OrdemTargets, RestartPolicy,
+    ServiceEntry,
+};
 
 /// Sample XML configuration with a single service entry containing all fields.
 const SAMPLE_FULL_SERVICE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
@@ -102,3 +105,121 @@ fn ordem_with_capacity() {
     assert!(ordem.is_empty());
     assert!(ordem.services.capacity() >= 10);
 }
+
+/// Verifies that the restart policy fields round-trip through XML and that an absent
+/// `<restart>` element defaults to `never`.
+#[test]
+fn restart_policy_round_trips_through_xml() {
+    const XML: &str = r#"<OrdemTargets>
+  <Service>
+    <name>SvcWithRestart</name>
+    <end_mode>Automatic</end_mode>
+    <restart>on-failure</restart>
+    <restart_delay_secs>10</restart_delay_secs>
+    <max_restarts>5</max_restarts>
+  </Service>
+  <Service>
+    <name>SvcWithoutRestart</name>
+    <end_mode>Automatic</end_mode>
+  </Service>
+</OrdemTargets>"#;
+
+    let ordem = parse_ordem(XML).expect("parse failed");
+    assert_eq!(ordem.services.len(), 2);
+
+    let with_restart = &ordem.services[0];
+    assert_eq!(with_restart.restart_policy(), RestartPolicy::OnFailure);
+    assert_eq!(with_restart.restart_delay().as_secs(), 10);
+    assert_eq!(with_restart.max_restarts(), 5);
+
+    let without_restart = &ordem.services[1];
+    assert_eq!(without_restart.restart_policy(), RestartPolicy::Never);
+
+    let output = write_progress_xml(&ordem).expect("serialize failed");
+    assert!(output.contains("on-failure"), "Output should preserve the restart policy");
+    assert!(
+        !output.contains("<restart>") || output.matches("<restart>").count() == 1,
+        "Unset restart policy should not be serialized"
+    );
+}
+
+/// Verifies that per-service CPU tuning fields round-trip through XML and that an
+/// entry omitting them falls back to the built-in defaults.
+#[test]
+fn cpu_tuning_round_trips_through_xml() {
+    const XML: &str = r#"<OrdemTargets>
+  <Service>
+    <name>SvcWithCpuTuning</name>
+    <end_mode>Automatic</end_mode>
+    <cpu_threshold>80</cpu_threshold>
+    <cpu_poll_interval_secs>2</cpu_poll_interval_secs>
+    <cpu_wait_timeout_secs>600</cpu_wait_timeout_secs>
+  </Service>
+  <Service>
+    <name>SvcWithoutCpuTuning</name>
+    <end_mode>Automatic</end_mode>
+  </Service>
+</OrdemTargets>"#;
+
+    let ordem = parse_ordem(XML).expect("parse failed");
+    assert_eq!(ordem.services.len(), 2);
+
+    let with_tuning = &ordem.services[0];
+    assert_eq!(with_tuning.cpu_threshold(), 80.0);
+    assert_eq!(with_tuning.cpu_poll_interval().as_secs(), 2);
+    assert_eq!(with_tuning.cpu_wait_timeout().as_secs(), 600);
+
+    let without_tuning = &ordem.services[1];
+    assert_eq!(without_tuning.cpu_threshold(), 60.0);
+    assert_eq!(without_tuning.cpu_poll_interval().as_secs(), 1);
+    assert_eq!(without_tuning.cpu_wait_timeout().as_secs(), 300);
+
+    let output = write_progress_xml(&ordem).expect("serialize failed");
+    assert!(output.contains("<cpu_threshold>80</cpu_threshold>"));
+    assert!(
+        !output.contains("SvcWithoutCpuTuning") || !output.contains("<cpu_threshold>60</cpu_threshold>"),
+        "Unset CPU tuning should not be serialized with the default value"
+    );
+}
+
+/// Verifies that timestamp fields round-trip through XML as typed `Timestamp` values,
+/// not opaque strings, and that duration math works on the result.
+#[test]
+fn timestamps_round_trip_and_support_duration_math() {
+    const XML: &str = r#"<OrdemTargets>
+  <Service>
+    <name>TimedSvc</name>
+    <start_processing_time>2026-01-01T10:00:00+00:00</start_processing_time>
+    <end_time>2026-01-01T10:00:05+00:00</end_time>
+  </Service>
+</OrdemTargets>"#;
+
+    let ordem = parse_ordem(XML).expect("parse failed");
+    let svc = &ordem.services[0];
+
+    let start = svc.start_processing_time.expect("start time parsed");
+    let end = svc.end_time.expect("end time parsed");
+    assert_eq!((end - start).num_seconds(), 5);
+
+    // Round-trip through XML and re-parse, rather than matching the serialized string
+    // directly: `start` carries the machine's local offset (see `timestamp.rs`), so the
+    // serialized text is only stable on a UTC host.
+    let output = write_progress_xml(&ordem).expect("serialize failed");
+    let reparsed = parse_ordem(&output).expect("re-parse failed");
+    assert_eq!(reparsed.services[0].start_processing_time.expect("start time re-parsed"), start);
+}
+
+/// Verifies that a malformed timestamp is rejected at `parse_ordem` time with a clear
+/// error, instead of silently producing a garbage or absent value.
+#[test]
+fn malformed_timestamp_is_rejected() {
+    const XML: &str = r#"<OrdemTargets>
+  <Service>
+    <name>BadSvc</name>
+    <start_processing_time>not-a-timestamp</start_processing_time>
+  </Service>
+</OrdemTargets>"#;
+
+    let err = parse_ordem(XML).expect_err("malformed timestamp should fail to parse");
+    assert!(err.to_string().contains("not-a-timestamp"), "error should name the bad value: {err}");
+}